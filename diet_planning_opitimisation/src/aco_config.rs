@@ -0,0 +1,218 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use crate::ant_colony::{AcoVariant, Heuristic};
+use crate::pheromone::PheromoneModel;
+use crate::problem::InvalidProblem;
+
+/// Which ants deposit pheromone in the Ant System and how much. Deposits grow with
+/// the objective of the path whatever its sign, see `deposit_quality`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum DepositRule {
+    /// Every ant deposits with a probability given by its quality relative to the iteration best
+    FitnessRatio,
+    /// Every ant deposits
+    AllAnts,
+    /// The `ants - 1` best ants of the iteration deposit weighted by their rank, the best
+    /// so far path deposits with the weight `ants`
+    RankBased {
+        #[serde(default = "default_rank_ants")]
+        ants: usize,
+    },
+    /// Every ant deposits and the best so far path adds `weight` times its own deposit
+    Elitist {
+        #[serde(default = "default_elite_weight")]
+        weight: f64,
+    },
+    /// Only the best ant of the iteration deposits
+    IterationBest,
+}
+
+fn default_rank_ants() -> usize {
+    6
+}
+
+fn default_elite_weight() -> f64 {
+    5.0
+}
+
+impl DepositRule {
+    pub fn rank_based() -> Self {
+        DepositRule::RankBased { ants: default_rank_ants() }
+    }
+
+    pub fn elitist() -> Self {
+        DepositRule::Elitist { weight: default_elite_weight() }
+    }
+}
+
+/// What the colony does once its search stagnated
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StagnationPolicy {
+    /// Keep running, the diagnostics are only recorded
+    Ignore,
+    /// End the run early
+    Stop,
+    /// Reinitialise the pheromone, the global best is kept
+    Reset,
+}
+
+/// Everything that tunes the ant colony, loadable from a TOML file. Missing keys
+/// take their default value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AcoConfig {
+    /// Number of ants in the colony
+    pub ants: usize,
+    /// Number of colony iterations
+    pub iterations: usize,
+    /// Share of the pheromone evaporating in each iteration (rho)
+    pub evaporation: f64,
+    pub deposit: DepositRule,
+    /// Pheromone level the trails start at
+    pub initial_pheromone: f64,
+    /// Derive the starting level from the greedy diet instead of `initial_pheromone`
+    pub greedy_init: bool,
+    /// Extra pheromone on the trails of the greedy diet, as a multiple of the starting level
+    pub greedy_bias: f64,
+    /// Servings of a single dish an ant may take, the problem's cap when unset
+    pub max_servings: Option<u32>,
+    /// Steps an ant may take before its path ends, every serving of every dish when unset
+    pub max_path_length: Option<usize>,
+    /// Improve every path by hill climbing before the pheromone update
+    pub local_search: bool,
+    /// Keep a snapshot of the pheromone trails every this many iterations
+    pub snapshot_every: Option<usize>,
+    pub stagnation: StagnationPolicy,
+    /// The search stagnated once the ants build at most this many distinct diets
+    pub stagnation_paths: usize,
+    /// ... or once the average lambda-branching factor drops to this value
+    pub stagnation_branching: Option<f64>,
+    pub heuristic: Heuristic,
+    /// Weight of the pheromone in the dish choice
+    pub alpha: f64,
+    /// Weight of the heuristic in the dish choice
+    pub beta: f64,
+    pub pheromone: PheromoneModel,
+    pub variant: AcoVariant,
+    /// Seed for a reproducible run, a random one is used otherwise
+    pub seed: Option<u64>,
+}
+
+impl Default for AcoConfig {
+    fn default() -> Self {
+        Self {
+            ants: 1000,
+            iterations: 5000,
+            evaporation: 0.1,
+            deposit: DepositRule::FitnessRatio,
+            initial_pheromone: 1.0,
+            greedy_init: false,
+            greedy_bias: 0.0,
+            max_servings: None,
+            max_path_length: None,
+            local_search: false,
+            snapshot_every: None,
+            stagnation: StagnationPolicy::Ignore,
+            stagnation_paths: 1,
+            stagnation_branching: None,
+            heuristic: Heuristic::None,
+            alpha: 1.0,
+            beta: 2.0,
+            pheromone: PheromoneModel::Dish,
+            variant: AcoVariant::AntSystem,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(String),
+    /// A parameter is outside of the range the colony can work with
+    Invalid(String),
+    /// The problem the solver was set up for can't be solved
+    Problem(InvalidProblem),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "could not read config: {}", error),
+            ConfigError::Parse(message) => write!(f, "{}", message),
+            ConfigError::Invalid(message) => write!(f, "invalid config: {}", message),
+            ConfigError::Problem(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<InvalidProblem> for ConfigError {
+    fn from(error: InvalidProblem) -> Self {
+        ConfigError::Problem(error)
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(error: std::io::Error) -> Self {
+        ConfigError::Io(error)
+    }
+}
+
+pub(crate) fn check(valid: bool, message: &str) -> Result<(), ConfigError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(message.into()))
+    }
+}
+
+impl AcoConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+        let config: AcoConfig = toml::from_str(&contents).map_err(|error| ConfigError::Parse(error.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check(self.ants > 0, "ants must be at least 1")?;
+        check(self.iterations > 0, "iterations must be at least 1")?;
+        check(self.evaporation > 0.0 && self.evaporation < 1.0, "evaporation must be between 0 and 1")?;
+        check(self.initial_pheromone > 0.0 && self.initial_pheromone.is_finite(), "initial_pheromone must be positive")?;
+        check(self.greedy_bias >= 0.0 && self.greedy_bias.is_finite(), "greedy_bias must not be negative")?;
+        check(self.max_servings != Some(0), "max_servings must be at least 1")?;
+        check(self.max_path_length != Some(0), "max_path_length must be at least 1")?;
+        check(self.snapshot_every != Some(0), "snapshot_every must be at least 1")?;
+        check(self.stagnation_paths > 0, "stagnation_paths must be at least 1")?;
+        check(self.stagnation_branching.is_none_or(|branching| branching >= 1.0), "stagnation_branching must be at least 1")?;
+        check(self.alpha >= 0.0 && self.alpha.is_finite(), "alpha must not be negative")?;
+        check(self.beta >= 0.0 && self.beta.is_finite(), "beta must not be negative")?;
+        match self.deposit {
+            DepositRule::RankBased { ants } => check(ants >= 2, "rank based deposit needs at least 2 ranked ants")?,
+            DepositRule::Elitist { weight } => check(weight >= 0.0 && weight.is_finite(), "elitist weight must not be negative")?,
+            DepositRule::FitnessRatio | DepositRule::AllAnts | DepositRule::IterationBest => {}
+        }
+        match self.variant {
+            AcoVariant::AntSystem => Ok(()),
+            AcoVariant::MaxMin { p_best, stagnation_limit, .. } => {
+                check(p_best > 0.0 && p_best < 1.0, "p_best must be between 0 and 1")?;
+                check(stagnation_limit > 0, "stagnation_limit must be at least 1")
+            }
+            AcoVariant::ColonySystem { q0, xi } => {
+                check((0.0..=1.0).contains(&q0), "q0 must be between 0 and 1")?;
+                check((0.0..=1.0).contains(&xi), "xi must be between 0 and 1")
+            }
+            AcoVariant::PopulationBased { archive_size, weight, .. } => {
+                check(archive_size > 0, "archive_size must be at least 1")?;
+                check(weight > 0.0 && weight.is_finite(), "archive weight must be positive")
+            }
+        }
+    }
+}
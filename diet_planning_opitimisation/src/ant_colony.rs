@@ -1,184 +1,958 @@
 use plotters::prelude::*;
 use rayon::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
 use std::sync::Mutex;
-use crate::dish::{Dish, get_dishes};
+use std::time::{Duration, Instant};
+use crate::aco_config::{AcoConfig, ConfigError, DepositRule, StagnationPolicy};
+use crate::dish::Dish;
+use crate::greedy::greedy_diet;
+use crate::local_search::local_search;
+use crate::pheromone::{PheromoneModel, Snapshot, Trails};
+use crate::problem::{DietProblem, DietTotals};
+use crate::sampling::{sample_cumulative, AliasTable};
+use crate::solver::{Diagnostics, DishCount, IterationStats, RunResult, Solution, Solver, SolverError};
 
+/// A decision an ant takes while building its path
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub struct DishCount {
-    pub(crate) dish: usize,
-    pub(crate) count: usize,
+enum Move {
+    /// Add `servings` of the dish at index `dish`
+    Add { dish: usize, servings: usize },
+    /// End the path here, only offered once the nutrient targets are met
+    Stop,
 }
 
+/// Draws from the alias table that land on a closed choice before the ant falls
+/// back to weighing its open choices one by one
+const MAX_REJECTIONS: usize = 8;
+
 #[derive(Debug, Clone)]
 struct Ant {
-    current_dish: usize,
+    current_dish: Option<usize>,
     path: Vec<DishCount>,
-    total_price: u32,
-    total_calories: u32,
-    total_carbs: u32,
-    total_fats: u32,
-    total_proteins: u32,
+    /// Servings taken of every dish of the catalog
+    counts: Vec<usize>,
+    /// Position of every dish in `path`, only meaningful while its count isn't zero
+    slots: Vec<usize>,
+    /// Choices found to be infeasible, they never reopen as the diet only grows
+    closed: Vec<bool>,
+    /// Sampling weight of the closed choices, kept for the models with a single row of choices
+    closed_weight: f64,
+    /// Index of the pheromone trail followed at every step
+    trail: Vec<usize>,
+    totals: DietTotals,
     path_cost: f64,
+    stopped: bool,
 }
 
 impl Ant {
-    fn new() -> Self {
+    fn new(dishes: usize, choices: usize) -> Self {
         Self {
-            current_dish: 0,
+            current_dish: None,
             path: vec![],
-            total_price: 0,
-            total_calories: 0,
-            total_carbs: 0,
-            total_fats: 0,
-            total_proteins: 0,
+            counts: vec![0; dishes],
+            slots: vec![0; dishes],
+            closed: vec![false; choices],
+            closed_weight: 0.0,
+            trail: vec![],
+            totals: DietTotals::default(),
             path_cost: 0.0,
+            stopped: false,
+        }
+    }
+
+    /// Gets the ant ready for the next iteration without reallocating
+    fn reset(&mut self) {
+        for dish_count in self.path.drain(..) {
+            self.counts[dish_count.dish] = 0;
+        }
+        self.closed.fill(false);
+        self.closed_weight = 0.0;
+        self.current_dish = None;
+        self.trail.clear();
+        self.totals = DietTotals::default();
+        self.path_cost = 0.0;
+        self.stopped = false;
+    }
+
+    /// Runs the local search on the finished path and, when it improved the path,
+    /// retraces the trails of the improved one, keeping the ant's decision to stop
+    fn refine(&mut self, colony: &AntColony) {
+        let walked = self.path.clone();
+        self.totals = local_search(&mut self.path, &colony.dishes, &colony.problem, colony.max_servings);
+        if self.path == walked {
+            return;
         }
+        // the (dish, servings) of every step the ant took, in order
+        let moves = self.trail.len() - self.stopped as usize;
+        let steps: Vec<(usize, usize)> = self.trail[..moves].iter()
+            .map(|&index| colony.trails.choice(index % colony.trails.choices()))
+            .collect();
+        for dish_count in &walked {
+            self.counts[dish_count.dish] = 0;
+        }
+        self.path_cost = colony.problem.objective(&self.totals);
+        self.retrace(colony, &steps);
     }
 
-    fn construct_path(&mut self, dishes: &[Dish], pheromone: &[f64]) {
-        const PRICE_LIMIT: u32 = 200; // 20 zloty
-        const TARGET_CALORIES: u32 = 2250;
-        const TARGET_CARBS: u32 = 275;
-        const TARGET_FATS: u32 = 50;
-        const TARGET_PROTEINS: u32 = 120;
+    /// An ant that walked `path` in `colony`, used to bring in paths found elsewhere
+    fn following(path: &[DishCount], colony: &AntColony) -> Self {
+        let mut ant = Ant::new(colony.dishes.len(), colony.trails.choices());
+        ant.path = path.to_vec();
+        ant.totals = DietTotals::of(&colony.dishes, path.iter().map(|dish_count| (dish_count.dish, dish_count.count as u32)));
+        ant.path_cost = colony.problem.objective(&ant.totals);
+        ant.retrace(colony, &[]);
+        ant
+    }
 
-        while self.path.len() < dishes.len() * 10 && self.total_price <= PRICE_LIMIT {
-            let next_dish = self.select_next_dish(dishes, pheromone);
-            let dish_exists = self.path.iter_mut().find(|x| x.dish == next_dish);
-            match dish_exists {
-                Some(dish_count) => dish_count.count += 1,
-                None => self.path.push(DishCount { dish: next_dish, count: 1 }),
+    /// Rebuilds the counts and the trails followed from the path. The `walked` steps
+    /// still in the path are retraced in the order they were taken, so transitions
+    /// keep the pairings the ant actually made; the servings the path gained are
+    /// walked afterwards, dish by dish in path order.
+    fn retrace(&mut self, colony: &AntColony, walked: &[(usize, usize)]) {
+        for (slot, dish_count) in self.path.iter().enumerate() {
+            self.counts[dish_count.dish] = dish_count.count;
+            self.slots[dish_count.dish] = slot;
+        }
+        // servings of every dish not retraced yet
+        let mut left = self.counts.clone();
+        let mut steps: Vec<(usize, usize)> = walked.iter()
+            .copied()
+            .filter(|&(dish, servings)| {
+                let kept = match colony.trails.model() {
+                    // a portion is one step, it only survives with the same size
+                    PheromoneModel::Portion => left[dish] == servings,
+                    PheromoneModel::Dish | PheromoneModel::Transition => left[dish] >= servings,
+                };
+                if kept {
+                    left[dish] -= servings;
+                }
+                kept
+            })
+            .collect();
+        for dish_count in &self.path {
+            let servings = left[dish_count.dish];
+            match colony.trails.model() {
+                PheromoneModel::Portion if servings > 0 => steps.push((dish_count.dish, servings)),
+                PheromoneModel::Portion => {}
+                PheromoneModel::Dish | PheromoneModel::Transition => steps.extend(std::iter::repeat_n((dish_count.dish, 1), servings)),
             }
-            self.total_price += dishes[next_dish].price;
-            self.total_calories += dishes[next_dish].calories;
-            self.total_carbs += dishes[next_dish].carbs;
-            self.total_fats += dishes[next_dish].fats;
-            self.total_proteins += dishes[next_dish].proteins;
-            // self.path_cost = self.total_price as f64 - (self.total_price.pow(2) as f64) + (self.total_calories + self.total_carbs + self.total_fats + self.total_proteins) as f64;
-            let normalized_price = self.total_price as f64 / PRICE_LIMIT as f64;
-            let normalized_calories = self.total_calories as f64 / TARGET_CALORIES as f64;
-            let normalized_carbs = self.total_carbs as f64 / TARGET_CARBS as f64;
-            let normalized_fats = self.total_fats as f64 / TARGET_FATS as f64;
-            let normalized_proteins = self.total_proteins as f64 / TARGET_PROTEINS as f64;
-            self.path_cost = (normalized_calories + normalized_carbs + normalized_fats + normalized_proteins) - normalized_price;
-            if self.total_calories < 2050 || self.total_calories > 2450 {
-                self.path_cost -= (self.total_calories as i32 - 2250).abs() as f64;
+        }
+
+        self.trail.clear();
+        self.current_dish = None;
+        for (dish, servings) in steps {
+            self.trail.push(colony.trails.index(self.current_dish, dish, servings));
+            self.current_dish = Some(dish);
+        }
+        if self.stopped {
+            self.trail.push(colony.trails.stop_index(self.current_dish));
+        }
+    }
+
+    fn construct_path<R: Rng>(&mut self, colony: &AntColony, rng: &mut R) {
+        while self.can_continue(colony) {
+            if self.step(colony, rng).is_none() {
+                break;
+            }
+        }
+    }
+
+    /// Every step follows one trail, so the trail counts the steps taken
+    fn can_continue(&self, colony: &AntColony) -> bool {
+        !self.stopped && self.trail.len() < colony.max_path_length
+    }
+
+    /// Whether the ant may still take a choice: the dish has servings left (portions
+    /// are decided once per dish, the other models add a serving at a time) and it fits
+    /// the remaining budget and every nutrient's upper bound
+    fn is_open(&self, colony: &AntColony, choice: usize) -> bool {
+        let (dish, servings) = colony.trails.choice(choice);
+        let count = self.counts[dish];
+        let available = match colony.trails.model() {
+            PheromoneModel::Portion => count == 0,
+            PheromoneModel::Dish | PheromoneModel::Transition => count < colony.max_servings,
+        };
+        available && colony.problem.fits(&self.totals, &colony.dishes[dish], servings as u32)
+    }
+
+    fn close(&mut self, colony: &AntColony, choice: usize) {
+        if !self.closed[choice] {
+            self.closed[choice] = true;
+            if let [table] = colony.tables.as_slice() {
+                self.closed_weight += table.weight(choice);
             }
-            if self.total_carbs < 250 || self.total_carbs > 300 {
-                self.path_cost -= (self.total_carbs as i32 - 275).abs() as f64;
+        }
+    }
+
+    /// Every decision still open to the ant, stopping is only offered once the
+    /// nutrient targets are met
+    fn open_moves(&mut self, colony: &AntColony) -> Vec<Move> {
+        let mut moves = Vec::new();
+        for choice in 0..colony.trails.choices() {
+            if self.closed[choice] {
+                continue;
             }
-            if self.total_fats < 40 || self.total_fats > 60 {
-                self.path_cost -= (self.total_fats as i32 - 50).abs() as f64;
+            if self.is_open(colony, choice) {
+                let (dish, servings) = colony.trails.choice(choice);
+                moves.push(Move::Add { dish, servings });
+            } else {
+                self.close(colony, choice);
             }
-            if self.total_proteins < 100 || self.total_proteins > 140 {
-                self.path_cost -= (self.total_proteins as i32 - 120).abs() as f64;
+        }
+        if colony.problem.targets_met(&self.totals) {
+            moves.push(Move::Stop);
+        }
+        moves
+    }
+
+    /// Takes the next move and returns the trail it followed,
+    /// or `None` when there is nothing left to add
+    fn step<R: Rng>(&mut self, colony: &AntColony, rng: &mut R) -> Option<usize> {
+        let next = match colony.config.variant {
+            AcoVariant::ColonySystem { q0, .. } if rng.gen::<f64>() < q0 => self.best_move(colony),
+            _ => self.select_move(colony, rng),
+        }?;
+        let (next_dish, servings) = match next {
+            Move::Add { dish, servings } => (dish, servings),
+            Move::Stop => {
+                let trail = colony.trails.stop_index(self.current_dish);
+                self.trail.push(trail);
+                self.stopped = true;
+                return Some(trail);
             }
-            self.current_dish = next_dish;
-        }
-    }
-
-    fn select_next_dish(&self, dishes: &[Dish], pheromone: &[f64]) -> usize {
-        let mut rng = rand::thread_rng();
-        let total: f64 = dishes.iter().enumerate()
-            .filter(|&(i, _)| self.path.iter().find(|&x| x.dish == i).map_or(true, |x| x.count < 10))
-            .map(|(i, _)| pheromone[i])
-            .sum();
-        let mut prob: f64 = rng.gen();
-        for (i, dish) in dishes.iter().enumerate() {
-            if self.path.iter().find(|&x| x.dish == i).map_or(true, |x| x.count < 10) {
-                prob -= pheromone[i] / total;
-                if prob <= 0.0 {
-                    return i;
+        };
+        Some(self.add(colony, next_dish, servings))
+    }
+
+    /// Adds `servings` of `dish` to the path and returns the trail followed to it
+    fn add(&mut self, colony: &AntColony, dish: usize, servings: usize) -> usize {
+        if self.counts[dish] == 0 {
+            self.slots[dish] = self.path.len();
+            self.path.push(DishCount { dish, count: servings });
+        } else {
+            self.path[self.slots[dish]].count += servings;
+        }
+        self.counts[dish] += servings;
+        self.totals.add(&colony.dishes[dish], servings as u32);
+        self.path_cost = colony.problem.objective(&self.totals);
+        let trail = colony.trails.index(self.current_dish, dish, servings);
+        self.trail.push(trail);
+        self.current_dish = Some(dish);
+        trail
+    }
+
+    /// The exploiting choice of the pseudo-random proportional rule
+    fn best_move(&mut self, colony: &AntColony) -> Option<Move> {
+        self.open_moves(colony)
+            .into_iter()
+            .max_by(|&a, &b| self.desirability(colony, a).partial_cmp(&self.desirability(colony, b)).unwrap())
+    }
+
+    /// Draws a move proportionally to its desirability. While the colony has alias tables
+    /// the draw is O(1) with rejection of the closed choices; once too much of the table
+    /// is closed the open choices are summed up and sampled from their cumulative weights.
+    fn select_move<R: Rng>(&mut self, colony: &AntColony, rng: &mut R) -> Option<Move> {
+        if let Some(table) = colony.tables.get(colony.trails.row(self.current_dish)) {
+            let stop_weight = if colony.problem.targets_met(&self.totals) {
+                self.desirability(colony, Move::Stop)
+            } else {
+                0.0
+            };
+            for _ in 0..MAX_REJECTIONS {
+                if self.closed_weight > table.total() / 2.0 {
+                    break;
                 }
+                if rng.gen::<f64>() * (table.total() + stop_weight) < stop_weight {
+                    return Some(Move::Stop);
+                }
+                let choice = table.sample(rng);
+                if !self.closed[choice] && self.is_open(colony, choice) {
+                    let (dish, servings) = colony.trails.choice(choice);
+                    return Some(Move::Add { dish, servings });
+                }
+                self.close(colony, choice);
             }
         }
-        self.path[self.path.len() - 1].dish
+
+        let moves = self.open_moves(colony);
+        let cumulative: Vec<f64> = moves.iter()
+            .scan(0.0, |sum, &next| {
+                *sum += self.desirability(colony, next);
+                Some(*sum)
+            })
+            .collect();
+        sample_cumulative(&cumulative, rng).map(|index| moves[index])
+    }
+
+    /// tau^alpha * eta^beta of a move, stopping is neutral to the heuristic
+    fn desirability(&self, colony: &AntColony, next: Move) -> f64 {
+        let (dish, servings) = match next {
+            Move::Add { dish, servings } => (dish, servings),
+            Move::Stop => return colony.trails.stop_level(self.current_dish).powf(colony.config.alpha),
+        };
+        let eta = match colony.config.heuristic {
+            Heuristic::GapClosing => gap_closing(&colony.dishes[dish], servings as u32, &colony.problem, &self.totals),
+            _ => colony.eta[dish],
+        };
+        colony.trails.level(self.current_dish, dish, servings).powf(colony.config.alpha) * eta.powf(colony.config.beta)
+    }
+}
+
+/// Keeps a dish selectable even when it scores nothing on the heuristic
+const MIN_HEURISTIC: f64 = 1e-6;
+
+/// Heuristic information (eta) guiding the ants next to the pheromone
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Heuristic {
+    /// No heuristic, ants follow the pheromone alone
+    None,
+    /// Nutrients, each normalised by its target, per unit of price
+    NutrientDensity,
+    /// Grams of protein per unit of price
+    ProteinDensity,
+    /// How much of the gap still left to the targets one serving closes, per unit of price
+    GapClosing,
+}
+
+impl Heuristic {
+    /// Eta of every dish for the heuristics that don't depend on the ant's diet so far
+    fn static_eta(&self, dishes: &[Dish], problem: &DietProblem) -> Vec<f64> {
+        dishes.iter()
+            .map(|dish| match self {
+                Heuristic::None | Heuristic::GapClosing => 1.0,
+                Heuristic::NutrientDensity => problem.nutrient_density(dish).max(MIN_HEURISTIC),
+                Heuristic::ProteinDensity => (dish.proteins as f64 / dish.price as f64).max(MIN_HEURISTIC),
+            })
+            .collect()
+    }
+}
+
+fn gap_closing(dish: &Dish, servings: u32, problem: &DietProblem, totals: &DietTotals) -> f64 {
+    (problem.gap_closed(totals, dish, servings) / (dish.price as f64 * servings as f64)).max(MIN_HEURISTIC)
+}
+
+/// Maps a path cost of any sign to a positive deposit that grows with the cost
+fn deposit_quality(path_cost: f64) -> f64 {
+    if path_cost >= 0.0 {
+        1.0 + path_cost
+    } else {
+        1.0 / (1.0 - path_cost)
+    }
+}
+
+/// Which ant is allowed to deposit pheromone in the MAX-MIN Ant System
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BestAnt {
+    IterationBest,
+    GlobalBest,
+}
+
+/// How the colony learns from the paths of its ants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum AcoVariant {
+    /// Every ant deposits following the configured `DepositRule`
+    AntSystem,
+    /// MAX-MIN Ant System: a single best ant deposits, trails are kept within
+    /// [tau_min, tau_max] and reinitialised once the search stagnates
+    MaxMin {
+        #[serde(default = "default_best_ant")]
+        best_ant: BestAnt,
+        /// Probability of an ant rebuilding the best path once the trails converged,
+        /// tau_min is derived from it
+        #[serde(default = "default_p_best")]
+        p_best: f64,
+        /// Iterations without a new global best after which the trails are reset
+        #[serde(default = "default_stagnation_limit")]
+        stagnation_limit: usize,
+    },
+    /// Ant Colony System: ants exploit the most desirable dish with probability q0,
+    /// decay the trail of every dish they take and only the global best deposits
+    ColonySystem {
+        #[serde(default = "default_q0")]
+        q0: f64,
+        /// Strength of the local pheromone decay towards tau0
+        #[serde(default = "default_xi")]
+        xi: f64,
+    },
+    /// Population-based ACO: the pheromone is derived from an archive of good paths
+    /// instead of evaporating, the iteration best enters the archive every iteration
+    PopulationBased {
+        /// Paths kept in the archive
+        #[serde(default = "default_archive_size")]
+        archive_size: usize,
+        #[serde(default = "default_replacement")]
+        replacement: Replacement,
+        /// Pheromone every archived path adds to its trails, as a multiple of the initial level
+        #[serde(default = "default_archive_weight")]
+        weight: f64,
+    },
+}
+
+/// Which path leaves the P-ACO archive once it is full
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Replacement {
+    /// The oldest one
+    Age,
+    /// The worst one, the newcomer itself when it is worse than every archived path
+    Quality,
+    /// The one sharing most servings with the newcomer, which keeps the archive diverse
+    Diversity,
+}
+
+fn default_best_ant() -> BestAnt {
+    BestAnt::IterationBest
+}
+
+fn default_p_best() -> f64 {
+    0.05
+}
+
+fn default_stagnation_limit() -> usize {
+    50
+}
+
+fn default_q0() -> f64 {
+    0.9
+}
+
+fn default_xi() -> f64 {
+    0.1
+}
+
+fn default_archive_size() -> usize {
+    5
+}
+
+fn default_replacement() -> Replacement {
+    Replacement::Age
+}
+
+fn default_archive_weight() -> f64 {
+    1.0
+}
+
+impl AcoVariant {
+    pub fn max_min() -> Self {
+        AcoVariant::MaxMin {
+            best_ant: default_best_ant(),
+            p_best: default_p_best(),
+            stagnation_limit: default_stagnation_limit(),
+        }
+    }
+
+    pub fn colony_system() -> Self {
+        AcoVariant::ColonySystem { q0: default_q0(), xi: default_xi() }
+    }
+
+    pub fn population_based() -> Self {
+        AcoVariant::PopulationBased {
+            archive_size: default_archive_size(),
+            replacement: default_replacement(),
+            weight: default_archive_weight(),
+        }
     }
 }
 
+/// Servings two diets have in common
+fn shared_servings(a: &Ant, b: &Ant) -> usize {
+    a.path.iter()
+        .filter(|dish_count| b.counts[dish_count.dish] > 0)
+        .map(|dish_count| dish_count.count.min(b.counts[dish_count.dish]))
+        .sum()
+}
+
+/// Lambda of the branching factor reported in the diagnostics, the usual choice in the literature
+const BRANCHING_LAMBDA: f64 = 0.05;
+
+/// Independent random stream of one ant in one iteration. Deriving it from the
+/// colony seed instead of sharing an rng keeps runs identical whatever the rayon
+/// thread count or scheduling is.
+fn ant_rng(seed: u64, iteration: usize, ant: usize) -> StdRng {
+    let mut key = [0u8; 32];
+    key[..8].copy_from_slice(&seed.to_le_bytes());
+    key[8..16].copy_from_slice(&(iteration as u64).to_le_bytes());
+    key[16..24].copy_from_slice(&(ant as u64).to_le_bytes());
+    StdRng::from_seed(key)
+}
+
+/// What a call to `AntColony::run` produced
+#[derive(Debug, Clone)]
+pub struct ColonyRun {
+    /// Best diet found since the colony was created
+    pub best: Solution,
+    /// Iteration of the colony the best diet was found in
+    pub found_in: usize,
+    /// Best objective found so far, one entry per iteration run
+    pub history: Vec<f64>,
+    pub iterations: Vec<IterationStats>,
+    pub elapsed: Duration,
+}
+
 #[derive(Debug, Clone)]
 pub struct AntColony {
-    pub(crate) dishes: Vec<Dish>,
-    pheromone: Vec<f64>,
+    dishes: Vec<Dish>,
+    problem: DietProblem,
+    config: AcoConfig,
+    trails: Trails,
+    /// Level the trails start at, the ACS local update decays them towards it
+    tau0: f64,
+    /// Objective of the greedy diet when the trails start from it
+    baseline: Option<f64>,
+    /// Trails of the greedy diet, biased whenever the trails are (re)initialised
+    greedy_trail: Vec<usize>,
+    /// Precomputed eta of each dish for the heuristics that allow it
+    eta: Vec<f64>,
+    /// Servings of a single dish an ant may take
+    max_servings: usize,
+    max_path_length: usize,
     ants: Vec<Ant>,
-    pub(crate) best_path: Vec<DishCount>,
-    best_ant: Option<Ant>
+    /// Alias table of every row of choices, rebuilt after each pheromone update. Left
+    /// empty when the weights change while the ants walk (gap closing heuristic, ACS).
+    tables: Vec<AliasTable>,
+    best_ant: Option<Ant>,
+    /// Iteration the best ant was found in
+    best_found_in: usize,
+    seed: u64,
+    /// Used for the colony level decisions, the ants get their own streams
+    rng: StdRng,
+    iteration: usize,
+    /// Iterations since the global best last improved
+    stagnant_iterations: usize,
+    snapshots: Vec<Snapshot>,
+    diagnostics: Vec<Diagnostics>,
+    /// Paths the P-ACO pheromone is derived from, oldest first
+    archive: Vec<Ant>,
 }
 
 impl AntColony {
-    pub(crate) fn new(dishes: Vec<Dish>, num_ants: usize) -> Self {
-        let pheromone = vec![1.0; dishes.len()];
-        let ants = vec![Ant::new(); num_ants];
-        Self {
+    /// Sets up a colony for `problem`, failing when the config is invalid
+    pub(crate) fn new(dishes: Vec<Dish>, problem: DietProblem, config: AcoConfig) -> Result<Self, ConfigError> {
+        problem.validate()?;
+        config.validate()?;
+        if dishes.is_empty() {
+            return Err(ConfigError::Invalid("the colony needs at least one dish".into()));
+        }
+        let max_servings = config.max_servings.unwrap_or(problem.max_servings);
+        if max_servings > problem.max_servings {
+            return Err(ConfigError::Invalid(format!(
+                "max_servings {} is above the cap of the problem ({})", max_servings, problem.max_servings,
+            )));
+        }
+        let max_servings = max_servings as usize;
+        let max_path_length = config.max_path_length.unwrap_or(dishes.len() * max_servings);
+        let trails = Trails::new(config.pheromone, dishes.len(), max_servings, config.initial_pheromone);
+        let ants = vec![Ant::new(dishes.len(), trails.choices()); config.ants];
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let eta = config.heuristic.static_eta(&dishes, &problem);
+        let mut colony = Self {
             dishes,
-            pheromone,
+            problem,
+            tau0: config.initial_pheromone,
+            baseline: None,
+            greedy_trail: vec![],
+            config,
+            trails,
+            eta,
+            max_servings,
+            max_path_length,
             ants,
-            best_path: vec![],
+            tables: vec![],
             best_ant: None,
+            best_found_in: 0,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            iteration: 0,
+            stagnant_iterations: 0,
+            snapshots: vec![],
+            diagnostics: vec![],
+            archive: vec![],
+        };
+        if colony.config.greedy_init {
+            colony.start_from_greedy();
         }
+        Ok(colony)
+    }
+
+    /// Starts the trails at the level they would settle on if the greedy diet were
+    /// deposited every iteration, with the extra `greedy_bias` on the trails of that diet
+    fn start_from_greedy(&mut self) {
+        let diet = greedy_diet(&self.dishes, &self.problem, self.max_servings);
+        let greedy = Ant::following(&diet, self);
+        self.tau0 = deposit_quality(greedy.path_cost) / self.config.evaporation;
+        self.greedy_trail = greedy.trail;
+        self.initialise_trails(self.tau0);
+        self.baseline = Some(greedy.path_cost);
+    }
+
+    /// Level the variant starts its trails at, MAX-MIN uses the tau_max of the global best
+    fn starting_level(&self) -> f64 {
+        match (&self.config.variant, &self.best_ant) {
+            (AcoVariant::MaxMin { .. }, Some(best)) => deposit_quality(best.path_cost) / self.config.evaporation,
+            _ => self.tau0,
+        }
+    }
+
+    /// Fills the trails with `level`, plus the `greedy_bias` on the greedy diet's trails
+    fn initialise_trails(&mut self, level: f64) {
+        self.trails.levels_mut().fill(level);
+        self.trails.deposit(&self.greedy_trail, self.config.greedy_bias * level);
+    }
+
+    /// Objective of the greedy diet the trails started from
+    pub(crate) fn baseline(&self) -> Option<f64> {
+        self.baseline
     }
 
     fn reset_ants(&mut self) {
         for ant in &mut self.ants {
-            *ant = Ant::new();
+            ant.reset();
+        }
+    }
+
+    fn refresh_tables(&mut self) {
+        self.tables.clear();
+        if self.config.heuristic == Heuristic::GapClosing || matches!(self.config.variant, AcoVariant::ColonySystem { .. }) {
+            return;
+        }
+        for row in 0..self.trails.rows() {
+            let weights = self.trails.row_levels(row)
+                .iter()
+                .enumerate()
+                .map(|(choice, level)| level.powf(self.config.alpha) * self.eta[self.trails.choice(choice).0].powf(self.config.beta))
+                .collect();
+            self.tables.push(AliasTable::new(weights));
         }
     }
 
     fn update_pheromones(&mut self) {
-        const EVAPORATION_RATE: f64 = 0.9; // 10% of the pheromone evaporates in each iteration
-        // Pheromone evaporation
-        for pheromone_level in &mut self.pheromone {
-            *pheromone_level *= EVAPORATION_RATE;
+        match self.config.variant {
+            AcoVariant::AntSystem => self.ant_system_update(),
+            AcoVariant::MaxMin { best_ant, p_best, stagnation_limit } => self.max_min_update(best_ant, p_best, stagnation_limit),
+            AcoVariant::ColonySystem { .. } => self.colony_system_update(),
+            AcoVariant::PopulationBased { archive_size, replacement, weight } => self.population_update(archive_size, replacement, weight),
+        }
+    }
+
+    /// P-ACO: the iteration best enters the archive, pushing a path out once it is full,
+    /// and the trails are rebuilt from the archived paths
+    fn population_update(&mut self, archive_size: usize, replacement: Replacement, weight: f64) {
+        let newcomer = self.ants.iter().max_by(|a, b| a.path_cost.partial_cmp(&b.path_cost).unwrap()).unwrap().clone();
+        self.archive.push(newcomer);
+        if self.archive.len() > archive_size {
+            let newcomer = &self.archive[self.archive.len() - 1];
+            let leaving = match replacement {
+                Replacement::Age => 0,
+                Replacement::Quality => self.archive.iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| a.path_cost.partial_cmp(&b.path_cost).unwrap())
+                    .map(|(index, _)| index)
+                    .unwrap(),
+                Replacement::Diversity => self.archive[..self.archive.len() - 1].iter()
+                    .enumerate()
+                    .max_by_key(|(_, archived)| shared_servings(archived, newcomer))
+                    .map(|(index, _)| index)
+                    .unwrap(),
+            };
+            self.archive.remove(leaving);
         }
 
-        // Find the best ant
-        let best_ant = self.ants.iter().max_by(|a, b| a.path_cost.partial_cmp(&b.path_cost).unwrap()).unwrap();
+        let tau0 = self.tau0;
+        self.trails.levels_mut().fill(tau0);
+        for archived in &self.archive {
+            self.trails.deposit(&archived.trail, weight * tau0);
+        }
+    }
+
+    /// Global ACS update, evaporation and deposit only touch the global best path
+    fn colony_system_update(&mut self) {
+        let best_ant = self.best_ant.as_ref().unwrap();
+        let rho = self.config.evaporation;
+        let deposit = deposit_quality(best_ant.path_cost);
+        let mut trail = best_ant.trail.clone();
+        trail.sort_unstable();
+        trail.dedup();
+        let levels = self.trails.levels_mut();
+        for index in trail {
+            levels[index] = (1.0 - rho) * levels[index] + rho * deposit;
+        }
+    }
+
+    fn ant_system_update(&mut self) {
+        // Pheromone evaporation
+        self.trails.evaporate(self.config.evaporation);
+
+        // Ants from the best to the worst path of the iteration
+        let mut ranked: Vec<&Ant> = self.ants.iter().collect();
+        ranked.sort_by(|a, b| b.path_cost.partial_cmp(&a.path_cost).unwrap());
+        let best_ant = ranked[0];
+        let global_best = self.best_ant.as_ref().unwrap();
 
         // Pheromone deposit
-        for ant in &self.ants {
-            let fitness_ratio = ant.path_cost / best_ant.path_cost;
-            let random_number: f64 = rand::random();
-            if random_number <= fitness_ratio {
-                for &dish in &ant.path {
-                    self.pheromone[dish.dish] += 1.0 / ant.path_cost;
+        match self.config.deposit {
+            DepositRule::FitnessRatio => {
+                let best_quality = deposit_quality(best_ant.path_cost);
+                for ant in &ranked {
+                    let quality = deposit_quality(ant.path_cost);
+                    let random_number: f64 = self.rng.gen();
+                    if random_number <= quality / best_quality {
+                        self.trails.deposit(&ant.trail, quality);
+                    }
+                }
+            }
+            DepositRule::AllAnts => {
+                for ant in &ranked {
+                    self.trails.deposit(&ant.trail, deposit_quality(ant.path_cost));
+                }
+            }
+            DepositRule::RankBased { ants } => {
+                for (rank, ant) in ranked.iter().take(ants - 1).enumerate() {
+                    self.trails.deposit(&ant.trail, (ants - 1 - rank) as f64 * deposit_quality(ant.path_cost));
                 }
+                self.trails.deposit(&global_best.trail, ants as f64 * deposit_quality(global_best.path_cost));
+            }
+            DepositRule::Elitist { weight } => {
+                for ant in &ranked {
+                    self.trails.deposit(&ant.trail, deposit_quality(ant.path_cost));
+                }
+                self.trails.deposit(&global_best.trail, weight * deposit_quality(global_best.path_cost));
+            }
+            DepositRule::IterationBest => self.trails.deposit(&best_ant.trail, deposit_quality(best_ant.path_cost)),
+        }
+    }
+
+    fn max_min_update(&mut self, best_ant: BestAnt, p_best: f64, stagnation_limit: usize) {
+        let global_best = self.best_ant.as_ref().unwrap();
+        let tau_max = self.starting_level();
+        // Stützle & Hoos: the bound at which an ant rebuilds the best path with probability p_best
+        let p_dec = p_best.powf(1.0 / self.trails.choices() as f64);
+        let average_choices = (self.trails.choices() as f64 / 2.0 - 1.0).max(1.0);
+        let tau_min = (tau_max * (1.0 - p_dec) / (average_choices * p_dec)).min(tau_max);
+
+        // tau_max is only known once there is a best ant, so that is when the trails start
+        if self.iteration == 1 || self.stagnant_iterations >= stagnation_limit {
+            self.initialise_trails(tau_max);
+            self.stagnant_iterations = 0;
+            return;
+        }
+
+        let depositor = match best_ant {
+            BestAnt::IterationBest => self.ants.iter().max_by(|a, b| a.path_cost.partial_cmp(&b.path_cost).unwrap()).unwrap(),
+            BestAnt::GlobalBest => global_best,
+        };
+        let deposit = deposit_quality(depositor.path_cost);
+        let trail = depositor.trail.clone();
+
+        self.trails.evaporate(self.config.evaporation);
+        self.trails.deposit(&trail, deposit);
+
+        for pheromone_level in self.trails.levels_mut() {
+            *pheromone_level = pheromone_level.clamp(tau_min, tau_max);
+        }
+    }
+
+    fn construct_paths(&mut self) {
+        // The ants read the whole colony while building their paths
+        let mut ants = std::mem::take(&mut self.ants);
+        ants.par_iter_mut().enumerate().for_each(|(index, ant)| {
+            let mut rng = ant_rng(self.seed, self.iteration, index);
+            ant.construct_path(self, &mut rng);
+        });
+        self.ants = ants;
+    }
+
+    /// ACS construction: all ants take one step in parallel, then the trails of the
+    /// dishes they took are decayed in ant order, which keeps runs reproducible
+    fn construct_paths_in_lockstep(&mut self, xi: f64) {
+        let mut ants = std::mem::take(&mut self.ants);
+        let mut rngs: Vec<StdRng> = (0..ants.len()).map(|index| ant_rng(self.seed, self.iteration, index)).collect();
+        loop {
+            let steps: Vec<Option<usize>> = ants.par_iter_mut()
+                .zip(rngs.par_iter_mut())
+                .map(|(ant, rng)| if ant.can_continue(self) { ant.step(self, rng) } else { None })
+                .collect();
+            if steps.iter().all(Option::is_none) {
+                break;
+            }
+            let levels = self.trails.levels_mut();
+            for index in steps.into_iter().flatten() {
+                levels[index] = (1.0 - xi) * levels[index] + xi * self.tau0;
             }
         }
+        self.ants = ants;
+    }
+
+    pub(crate) fn trails(&self) -> &Trails {
+        &self.trails
     }
 
-    pub(crate) fn run(&mut self, iterations: usize) -> Vec<f64> {
-        let mut best_fitnesses = Vec::new();
+    /// Best path found so far together with its objective
+    pub(crate) fn best(&self) -> Option<(&[DishCount], f64)> {
+        self.best_ant.as_ref().map(|ant| (ant.path.as_slice(), ant.path_cost))
+    }
+
+    /// Takes in a path found by another colony: pheromone is deposited along it and it
+    /// becomes the global best when it beats the colony's own
+    pub(crate) fn immigrate(&mut self, path: &[DishCount]) {
+        let ant = Ant::following(path, self);
+        self.trails.deposit(&ant.trail, deposit_quality(ant.path_cost));
+        if self.best_ant.as_ref().is_none_or(|best| ant.path_cost > best.path_cost) {
+            self.best_ant = Some(ant);
+            self.best_found_in = self.iteration;
+            self.stagnant_iterations = 0;
+        }
+    }
+
+    /// Moves every trail the share `weight` of the way towards `other`, which must have the same layout
+    pub(crate) fn blend_trails(&mut self, other: &Trails, weight: f64) {
+        for (level, other) in self.trails.levels_mut().iter_mut().zip(other.levels()) {
+            *level = (1.0 - weight) * *level + weight * other;
+        }
+    }
+
+    /// Ants times iterations run so far
+    pub(crate) fn evaluations(&self) -> u64 {
+        (self.ants.len() * self.iteration) as u64
+    }
+
+    /// Pheromone levels kept every `snapshot_every` iterations
+    pub(crate) fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    fn diagnose(&self) -> Diagnostics {
+        let paths: HashSet<Vec<(usize, usize)>> = self.ants.iter()
+            .map(|ant| {
+                let mut path: Vec<(usize, usize)> = ant.path.iter().map(|dish_count| (dish_count.dish, dish_count.count)).collect();
+                path.sort_unstable();
+                path
+            })
+            .collect();
+        Diagnostics {
+            entropy: self.trails.entropy(),
+            branching_factor: self.trails.branching_factor(BRANCHING_LAMBDA),
+            distinct_paths: paths.len(),
+        }
+    }
+
+    fn stagnated(&self, diagnostics: &Diagnostics) -> bool {
+        diagnostics.distinct_paths <= self.config.stagnation_paths
+            || self.config.stagnation_branching.is_some_and(|branching| diagnostics.branching_factor <= branching)
+    }
+
+    /// Convergence diagnostics of every iteration run so far
+    pub(crate) fn diagnostics(&self) -> &[Diagnostics] {
+        &self.diagnostics
+    }
+
+    fn refine_paths(&mut self) {
+        let mut ants = std::mem::take(&mut self.ants);
+        ants.par_iter_mut().for_each(|ant| ant.refine(self));
+        self.ants = ants;
+    }
+
+    /// Runs `iterations` more iterations, failing when no iteration has run at all
+    pub(crate) fn run(&mut self, iterations: usize) -> Result<ColonyRun, SolverError> {
+        let started_at = Instant::now();
+        let mut history = Vec::new();
+        let mut stats = Vec::new();
         for _ in 0..iterations {
             self.reset_ants();
-            self.ants.par_iter_mut().for_each(|ant| {
-                ant.construct_path(&self.dishes, &self.pheromone);
+            self.refresh_tables();
+            match self.config.variant {
+                AcoVariant::ColonySystem { xi, .. } => self.construct_paths_in_lockstep(xi),
+                _ => self.construct_paths(),
+            }
+            if self.config.local_search {
+                self.refine_paths();
+            }
+            self.iteration += 1;
+            self.stagnant_iterations += 1;
+            let best_ant = self.ants.iter().max_by(|a, b| a.path_cost.partial_cmp(&b.path_cost).unwrap()).unwrap();
+            if self.best_ant.as_ref().is_none_or(|best| best_ant.path_cost > best.path_cost) {
+                self.best_ant = Some(best_ant.clone());
+                self.best_found_in = self.iteration;
+                self.stagnant_iterations = 0;
+            }
+            stats.push(IterationStats {
+                best: best_ant.path_cost,
+                mean: self.ants.iter().map(|ant| ant.path_cost).sum::<f64>() / self.ants.len() as f64,
+                worst: self.ants.iter().map(|ant| ant.path_cost).fold(f64::MAX, f64::min),
             });
             self.update_pheromones();
-            if let Some(best_ant) = self.ants.iter().max_by(|a, b| a.path_cost.partial_cmp(&b.path_cost).unwrap()) {
-                if self.best_ant.is_none() || best_ant.path_cost > self.best_ant.as_ref().unwrap().path_cost {
-                    self.best_path = best_ant.path.clone();
-                    self.best_ant = Some(best_ant.clone());
+            if self.config.snapshot_every.is_some_and(|every| self.iteration.is_multiple_of(every)) {
+                self.snapshots.push(Snapshot { iteration: self.iteration, levels: self.trails.levels().to_vec() });
+            }
+            history.push(self.best_ant.as_ref().unwrap().path_cost);
+
+            let diagnostics = self.diagnose();
+            self.diagnostics.push(diagnostics);
+            if self.stagnated(&diagnostics) {
+                match self.config.stagnation {
+                    StagnationPolicy::Ignore => {}
+                    StagnationPolicy::Stop => break,
+                    StagnationPolicy::Reset => self.initialise_trails(self.starting_level()),
                 }
             }
-            best_fitnesses.push(self.best_ant.as_ref().unwrap().path_cost);
         }
-        best_fitnesses
+        let best_ant = self.best_ant.as_ref().ok_or(SolverError::NoIterations)?;
+        Ok(ColonyRun {
+            best: Solution::new(best_ant.path.clone(), &self.dishes, &self.problem),
+            found_in: self.best_found_in,
+            history,
+            iterations: stats,
+            elapsed: started_at.elapsed(),
+        })
     }
 }
 
-pub fn ant_colony_algorithm() {
-    sample_run();
+impl Solver for AntColony {
+    fn name(&self) -> String {
+        "ant colony".into()
+    }
+
+    fn solve(&mut self, iterations: usize) -> Result<RunResult, SolverError> {
+        // the colony keeps the diagnostics of earlier runs as well
+        let earlier_runs = self.diagnostics().len();
+        let run = self.run(iterations)?;
+        Ok(RunResult {
+            solver: self.name(),
+            best: run.best,
+            found_in: Some(run.found_in),
+            evaluations: (self.ants.len() * run.history.len()) as u64,
+            history: run.history,
+            iterations: run.iterations,
+            diagnostics: self.diagnostics()[earlier_runs..].to_vec(),
+            baseline: self.baseline,
+            seed: self.seed,
+            elapsed: run.elapsed,
+        })
+    }
+}
+
+/// Runs a colony for every combination of colony size and iteration count, starting
+/// from `config`, printing the best fitness of each and plotting them into `output_dir`
+pub fn ant_colony_algorithm(dishes: Vec<Dish>, problem: DietProblem, config: AcoConfig, num_ants_values: &[usize], num_iterations_values: &[usize], output_dir: &Path) -> Result<(), ConfigError> {
+    // fail before the sweep starts, the sizes of the grid are checked per colony below
+    AntColony::new(dishes.clone(), problem.clone(), config.clone())?;
+    if num_ants_values.contains(&0) || num_iterations_values.contains(&0) {
+        return Err(ConfigError::Invalid("ants and iterations must be at least 1".into()));
+    }
 
-    let dishes = get_dishes();
     let results = Mutex::new(Vec::new());
-    let num_ants_values: Vec<usize> = (0..=10).map(|i| 5 * 2usize.pow(i)).collect();
     num_ants_values.par_iter().for_each(|&num_ants| {
-        let num_iterations_values: Vec<usize> = (0..=10).map(|i| 5 * 2usize.pow(i)).collect();
         num_iterations_values.par_iter().for_each(|&num_iterations| {
-            let mut ant_colony = AntColony::new(dishes.clone(), num_ants);
-            let best_fitnesses = ant_colony.run(num_iterations);
-            let best_fitness = *best_fitnesses.last().unwrap();
+            let config = AcoConfig { ants: num_ants, iterations: num_iterations, ..config.clone() };
+            let mut ant_colony = AntColony::new(dishes.clone(), problem.clone(), config).expect("the config was validated above");
+            let best_fitness = ant_colony.run(num_iterations).expect("the iterations were checked above").best.objective;
             results.lock().unwrap().push((num_ants, num_iterations, best_fitness));
         });
     });
@@ -189,75 +963,15 @@ pub fn ant_colony_algorithm() {
 
     let results = results.lock().unwrap();
 
-    plot_for_each_num_ants(&results);
-    plot_for_each_num_iterations(&results);
-    plot_joint(&results);
+    plot_for_each_num_ants(&results, num_ants_values, output_dir);
+    plot_for_each_num_iterations(&results, num_iterations_values, output_dir);
+    plot_joint(&results, output_dir);
+    Ok(())
 }
 
-fn sample_run() {
-    let dishes = get_dishes();
-    let mut ant_colony = AntColony::new(dishes, 1000);
-    ant_colony.run(5000);
-    let best_path_names: Vec<String> = ant_colony.best_path
-        .iter()
-        .map(|dish_count| format!("dish: {}, count: {}", ant_colony.dishes[dish_count.dish].name, dish_count.count))
-        .collect();
-    println!("Best path: {:?}", best_path_names);
-
-    let total_calories: u32 = ant_colony.best_path
-        .iter()
-        .map(|dish_count| ant_colony.dishes[dish_count.dish].calories * dish_count.count as u32)
-        .sum();
-    let total_carbs: u32 = ant_colony.best_path
-        .iter()
-        .map(|dish_count| ant_colony.dishes[dish_count.dish].carbs * dish_count.count as u32)
-        .sum();
-    let total_fats: u32 = ant_colony.best_path
-        .iter()
-        .map(|dish_count| ant_colony.dishes[dish_count.dish].fats * dish_count.count as u32)
-        .sum();
-    let total_proteins: u32 = ant_colony.best_path
-        .iter()
-        .map(|dish_count| ant_colony.dishes[dish_count.dish].proteins * dish_count.count as u32)
-        .sum();
-    let total_price: u32 = ant_colony.best_path
-        .iter()
-        .map(|dish_count| ant_colony.dishes[dish_count.dish].price * dish_count.count as u32)
-        .sum();
-    let best_ant = ant_colony.best_ant.unwrap();
-
-    println!("Goal function value: {}", best_ant.path_cost);
-    println!("Total price: {}", total_price);
-    println!("Total calories: {}", total_calories);
-    println!("Total carbs: {}", total_carbs);
-    println!("Total fats: {}", total_fats);
-    println!("Total proteins: {}", total_proteins);
-}
-
-pub fn plot_best_fitness(best_fitnesses: &[f64]) {
-    let root = BitMapBackend::new("best_fitness.png", (640, 480)).into_drawing_area();
-    root.fill(&WHITE).unwrap();
-
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Best Fitness Over Generations", ("sans-serif", 20).into_font())
-        .margin(25)
-        .x_label_area_size(30)
-        .y_label_area_size(30)
-        .build_cartesian_2d(0f64..best_fitnesses.len() as f64, best_fitnesses.iter().cloned().fold(f64::MAX, f64::min)..best_fitnesses.iter().cloned().fold(f64::MIN, f64::max))
-        .unwrap();
-
-    chart.configure_mesh().draw().unwrap();
-
-    chart.draw_series(LineSeries::new(
-        best_fitnesses.iter().enumerate().map(|(i, fitness)| (i as f64, *fitness)),
-        &RED,
-    )).unwrap();
-}
-
-fn plot_for_each_num_ants(results: &[(usize, usize, f64)]) {
-    let num_ants_values: Vec<usize> = (0..=10).map(|i| 5 * 2usize.pow(i)).collect();
-    for &num_ants in &num_ants_values {
-        let filename = format!("plot_for_{}_ants.png", num_ants);
+fn plot_for_each_num_ants(results: &[(usize, usize, f64)], num_ants_values: &[usize], output_dir: &Path) {
+    for &num_ants in num_ants_values {
+        let filename = output_dir.join(format!("plot_for_{}_ants.png", num_ants));
         let root = BitMapBackend::new(&filename, (640, 480)).into_drawing_area();
         root.fill(&WHITE).unwrap();
 
@@ -284,10 +998,9 @@ fn plot_for_each_num_ants(results: &[(usize, usize, f64)]) {
     }
 }
 
-fn plot_for_each_num_iterations(results: &[(usize, usize, f64)]) {
-    let num_iterations_values: Vec<usize> = (0..=10).map(|i| 5 * 2usize.pow(i)).collect();
-    for &num_iterations in &num_iterations_values {
-        let filename = format!("plot_for_{}_iterations.png", num_iterations);
+fn plot_for_each_num_iterations(results: &[(usize, usize, f64)], num_iterations_values: &[usize], output_dir: &Path) {
+    for &num_iterations in num_iterations_values {
+        let filename = output_dir.join(format!("plot_for_{}_iterations.png", num_iterations));
         let root = BitMapBackend::new(&filename, (640, 480)).into_drawing_area();
         root.fill(&WHITE).unwrap();
 
@@ -310,8 +1023,8 @@ fn plot_for_each_num_iterations(results: &[(usize, usize, f64)]) {
     }
 }
 
-fn plot_joint(results: &[(usize, usize, f64)]) {
-    let filename = "joint_plot.png";
+fn plot_joint(results: &[(usize, usize, f64)], output_dir: &Path) {
+    let filename = output_dir.join("joint_plot.png");
     let root = BitMapBackend::new(&filename, (640, 480)).into_drawing_area();
     root.fill(&WHITE).unwrap();
 
@@ -333,4 +1046,76 @@ fn plot_joint(results: &[(usize, usize, f64)]) {
         .collect();
 
     chart.draw_series(LineSeries::new(data, &RED)).unwrap();
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::problem::Nutrients;
+    use super::*;
+
+    /// A dish with the same amount of every nutrient
+    fn dish(name: &str, amount: u32) -> Dish {
+        Dish { name: name.into(), calories: amount, price: 1, carbs: amount, fats: amount, proteins: amount }
+    }
+
+    /// Every nutrient has to hit 100 exactly, so local search moves are easy to predict
+    fn colony(dishes: Vec<Dish>, pheromone: PheromoneModel) -> AntColony {
+        let problem = DietProblem {
+            targets: Nutrients { calories: 100, carbs: 100, fats: 100, proteins: 100 },
+            tolerances: Nutrients::default(),
+            budget: 1000,
+            max_servings: 10,
+        };
+        AntColony::new(dishes, problem, AcoConfig { pheromone, ..AcoConfig::default() }).unwrap()
+    }
+
+    /// An ant that took `steps` and then stopped
+    fn walked(colony: &AntColony, steps: &[(usize, usize)]) -> Ant {
+        let mut ant = Ant::new(colony.dishes.len(), colony.trails.choices());
+        for &(dish, servings) in steps {
+            ant.add(colony, dish, servings);
+        }
+        ant.trail.push(colony.trails.stop_index(ant.current_dish));
+        ant.stopped = true;
+        ant
+    }
+
+    /// The trails an ant taking `steps` and then stopping follows
+    fn trail(colony: &AntColony, steps: &[(usize, usize)]) -> Vec<usize> {
+        walked(colony, steps).trail
+    }
+
+    #[test]
+    fn refining_keeps_the_walked_transitions_and_appends_the_gained_servings() {
+        let (carrot, rice, beans) = (0, 1, 2);
+        let colony = colony(vec![dish("carrot", 20), dish("rice", 30), dish("beans", 20)], PheromoneModel::Transition);
+        // 80 of every nutrient, one more carrot hits the targets
+        let mut ant = walked(&colony, &[(rice, 1), (beans, 1), (rice, 1)]);
+        ant.refine(&colony);
+        assert_eq!(ant.path, vec![DishCount { dish: rice, count: 2 }, DishCount { dish: beans, count: 1 }, DishCount { dish: carrot, count: 1 }]);
+        assert_eq!(ant.trail, trail(&colony, &[(rice, 1), (beans, 1), (rice, 1), (carrot, 1)]));
+        assert_eq!(ant.counts, vec![1, 2, 1]);
+        assert_eq!(ant.path_cost, colony.problem.objective(&ant.totals));
+    }
+
+    #[test]
+    fn refining_an_unimproved_path_keeps_its_trail() {
+        let (carrot, rice, beans) = (0, 1, 2);
+        let colony = colony(vec![dish("carrot", 20), dish("rice", 30), dish("beans", 20)], PheromoneModel::Transition);
+        let steps = [(rice, 1), (carrot, 1), (rice, 1), (beans, 1)];
+        let mut ant = walked(&colony, &steps);
+        ant.refine(&colony);
+        assert_eq!(ant.trail, trail(&colony, &steps));
+    }
+
+    #[test]
+    fn refining_walks_a_resized_portion_after_the_kept_ones() {
+        let (oats, beans) = (0, 1);
+        let colony = colony(vec![dish("oats", 10), dish("beans", 85)], PheromoneModel::Portion);
+        // 115 of every nutrient, one serving of oats less hits 105
+        let mut ant = walked(&colony, &[(oats, 3), (beans, 1)]);
+        ant.refine(&colony);
+        assert_eq!(ant.path, vec![DishCount { dish: oats, count: 2 }, DishCount { dish: beans, count: 1 }]);
+        assert_eq!(ant.trail, trail(&colony, &[(beans, 1), (oats, 2)]));
+        assert_eq!(ant.counts, vec![2, 1]);
+    }
+}
@@ -0,0 +1,95 @@
+use genevo::genetic::{Children, Parents};
+use genevo::operator::{CrossoverOp, GeneticOperator};
+use genevo::prelude::Rng;
+
+/// BLX-alpha for count genotypes: every gene of a child is drawn uniformly from the
+/// range spanned by its parents' genes, widened by `alpha` times that range on both
+/// sides, then rounded and kept within `[0, max_value]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlendCrossover {
+    pub alpha: f64,
+    pub max_value: u32,
+}
+
+impl GeneticOperator for BlendCrossover {
+    fn name() -> String {
+        "Blend-Cross-Breeder".to_string()
+    }
+}
+
+impl CrossoverOp<Vec<u32>> for BlendCrossover {
+    /// Breeds one child per parent, each parent with the next one of the tuple
+    fn crossover<R>(&self, parents: Parents<Vec<u32>>, rng: &mut R) -> Children<Vec<u32>>
+        where
+            R: Rng + Sized,
+    {
+        (0..parents.len())
+            .map(|index| {
+                let (father, mother) = (&parents[index], &parents[(index + 1) % parents.len()]);
+                father.iter().zip(mother)
+                    .map(|(&a, &b)| {
+                        let (low, high) = (a.min(b) as f64, a.max(b) as f64);
+                        let spread = self.alpha * (high - low);
+                        let value = low - spread + rng.gen::<f64>() * (high - low + 2.0 * spread);
+                        value.round().clamp(0.0, self.max_value as f64) as u32
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Every child is a weighted average of two parents, the weight drawn once per child,
+/// with its genes rounded to whole servings
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArithmeticCrossover;
+
+impl GeneticOperator for ArithmeticCrossover {
+    fn name() -> String {
+        "Arithmetic-Cross-Breeder".to_string()
+    }
+}
+
+impl CrossoverOp<Vec<u32>> for ArithmeticCrossover {
+    /// Breeds one child per parent, each parent with the next one of the tuple
+    fn crossover<R>(&self, parents: Parents<Vec<u32>>, rng: &mut R) -> Children<Vec<u32>>
+        where
+            R: Rng + Sized,
+    {
+        (0..parents.len())
+            .map(|index| {
+                let (father, mother) = (&parents[index], &parents[(index + 1) % parents.len()]);
+                let weight = rng.gen::<f64>();
+                father.iter().zip(mother)
+                    .map(|(&a, &b)| (weight * a as f64 + (1.0 - weight) * b as f64).round() as u32)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use super::*;
+
+    #[test]
+    fn blended_genes_stay_within_bounds() {
+        let max_value = 10;
+        // alpha widens the parents' range far past both bounds
+        let crossover = BlendCrossover { alpha: 2.0, max_value };
+        let mut rng = StdRng::seed_from_u64(7);
+        let parents = vec![vec![0, 10, 0, 9, 1], vec![10, 0, 1, 10, 0], vec![5, 5, 0, 10, 10]];
+        let mut genes = Vec::new();
+        for _ in 0..100 {
+            for child in crossover.crossover(parents.clone(), &mut rng) {
+                assert_eq!(child.len(), parents[0].len());
+                genes.extend(child);
+            }
+        }
+        assert!(genes.iter().all(|&gene| gene <= max_value));
+        // the clamping keeps both bounds reachable
+        assert!(genes.contains(&0) && genes.contains(&max_value));
+    }
+}
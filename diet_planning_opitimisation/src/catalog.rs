@@ -0,0 +1,284 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use crate::dish::Dish;
+
+/// Supported catalog file formats, picked by file extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CatalogFormat {
+    Csv,
+    Json,
+    Toml,
+}
+
+impl CatalogFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(CatalogFormat::Csv),
+            "json" => Some(CatalogFormat::Json),
+            "toml" => Some(CatalogFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CatalogError {
+    Io(std::io::Error),
+    UnknownFormat(String),
+    /// The file could not be parsed into dish records
+    Parse { line: Option<usize>, message: String },
+    /// A record was parsed but describes an unusable dish
+    Invalid { line: usize, message: String },
+    Empty,
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CatalogError::Io(error) => write!(f, "could not read catalog: {}", error),
+            CatalogError::UnknownFormat(path) => {
+                write!(f, "unknown catalog format for {} (expected .csv, .json or .toml)", path)
+            }
+            CatalogError::Parse { line: Some(line), message } => write!(f, "line {}: {}", line, message),
+            CatalogError::Parse { line: None, message } => write!(f, "{}", message),
+            CatalogError::Invalid { line, message } => write!(f, "line {}: {}", line, message),
+            CatalogError::Empty => write!(f, "catalog contains no dishes"),
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+impl From<std::io::Error> for CatalogError {
+    fn from(error: std::io::Error) -> Self {
+        CatalogError::Io(error)
+    }
+}
+
+/// Loads a dish catalog, choosing the parser from the file extension
+pub fn load_catalog<P: AsRef<Path>>(path: P) -> Result<Vec<Dish>, CatalogError> {
+    let path = path.as_ref();
+    let format = CatalogFormat::from_path(path)
+        .ok_or_else(|| CatalogError::UnknownFormat(path.display().to_string()))?;
+    let contents = fs::read_to_string(path)?;
+    parse_catalog(&contents, format)
+}
+
+pub fn parse_catalog(contents: &str, format: CatalogFormat) -> Result<Vec<Dish>, CatalogError> {
+    let records = match format {
+        CatalogFormat::Csv => parse_csv(contents)?,
+        CatalogFormat::Json => parse_json(contents)?,
+        CatalogFormat::Toml => parse_toml(contents)?,
+    };
+    validate(records)
+}
+
+/// Every parser yields the dish together with the line it starts on
+type Record = (usize, Dish);
+
+fn parse_csv(contents: &str) -> Result<Vec<Record>, CatalogError> {
+    let csv_error = |error: csv::Error| CatalogError::Parse {
+        line: error.position().map(|position| line_of_offset(contents, position.byte() as usize)),
+        message: csv_message(&error),
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(contents.as_bytes());
+    let headers = reader.headers().map_err(csv_error)?.clone();
+
+    reader.records()
+        .map(|record| {
+            let record = record.map_err(csv_error)?;
+            // csv positions a record before the blank lines it skips, so step over them ourselves
+            let line = record.position().map_or(0, |position| {
+                let start = position.byte() as usize;
+                let skipped = contents[start..].len() - contents[start..].trim_start_matches(['\r', '\n']).len();
+                line_of_offset(contents, start + skipped)
+            });
+            record.deserialize::<Dish>(Some(&headers))
+                .map(|dish| (line, dish))
+                .map_err(|error| CatalogError::Parse { line: Some(line), message: csv_message(&error) })
+        })
+        .collect()
+}
+
+fn csv_message(error: &csv::Error) -> String {
+    match error.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => err.to_string(),
+        _ => error.to_string(),
+    }
+}
+
+fn parse_json(contents: &str) -> Result<Vec<Record>, CatalogError> {
+    let json_error = |error: serde_json::Error| CatalogError::Parse {
+        line: Some(error.line()),
+        message: json_message(&error),
+    };
+
+    // Raw values borrow from `contents`, so their offset tells us where each record starts
+    let raw_records: Vec<&RawValue> = serde_json::from_str(contents).map_err(json_error)?;
+    raw_records.into_iter()
+        .map(|raw| {
+            let offset = raw.get().as_ptr() as usize - contents.as_ptr() as usize;
+            let line = line_of_offset(contents, offset);
+            serde_json::from_str::<Dish>(raw.get())
+                .map(|dish| (line, dish))
+                .map_err(|error| CatalogError::Parse {
+                    line: Some(line + error.line() - 1),
+                    message: json_message(&error),
+                })
+        })
+        .collect()
+}
+
+/// serde_json appends its own (record-relative) position, which we report separately
+fn json_message(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    match message.rfind(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message,
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlCatalog {
+    #[serde(default)]
+    dish: Vec<toml::Spanned<Dish>>,
+}
+
+fn parse_toml(contents: &str) -> Result<Vec<Record>, CatalogError> {
+    let catalog: TomlCatalog = toml::from_str(contents).map_err(|error| CatalogError::Parse {
+        line: error.span().map(|span| line_of_offset(contents, span.start)),
+        message: error.message().to_string(),
+    })?;
+
+    Ok(catalog.dish
+        .into_iter()
+        .map(|spanned| (line_of_offset(contents, spanned.span().start), spanned.into_inner()))
+        .collect())
+}
+
+fn line_of_offset(contents: &str, offset: usize) -> usize {
+    contents[..offset].matches('\n').count() + 1
+}
+
+fn validate(records: Vec<Record>) -> Result<Vec<Dish>, CatalogError> {
+    if records.is_empty() {
+        return Err(CatalogError::Empty);
+    }
+
+    let mut names = HashSet::new();
+    let mut dishes = Vec::with_capacity(records.len());
+    for (line, dish) in records {
+        let invalid = |message: String| CatalogError::Invalid { line, message };
+        if dish.name.trim().is_empty() {
+            return Err(invalid("dish name must not be empty".into()));
+        }
+        if dish.price == 0 {
+            return Err(invalid(format!("dish '{}' must have a non-zero price", dish.name)));
+        }
+        if dish.calories == 0 && dish.carbs == 0 && dish.fats == 0 && dish.proteins == 0 {
+            return Err(invalid(format!("dish '{}' has no nutritional value", dish.name)));
+        }
+        if !names.insert(dish.name.clone()) {
+            return Err(invalid(format!("duplicate dish '{}'", dish.name)));
+        }
+        dishes.push(dish);
+    }
+    Ok(dishes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(records: Vec<Record>) -> Vec<(usize, String)> {
+        records.into_iter().map(|(line, dish)| (line, dish.name)).collect()
+    }
+
+    fn parse_line(result: Result<Vec<Record>, CatalogError>) -> Option<usize> {
+        match result {
+            Err(CatalogError::Parse { line, .. }) => line,
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn csv_records_start_after_the_blank_lines_before_them() {
+        let contents = "name,calories,price,carbs,fats,proteins\n\
+                        rice,350,5,77,1,7\n\
+                        \n\
+                        \n\
+                        eggs,155,10,1,11,13\n";
+        assert_eq!(lines(parse_csv(contents).unwrap()), vec![(2, "rice".into()), (5, "eggs".into())]);
+    }
+
+    #[test]
+    fn csv_errors_report_the_line_of_the_record() {
+        let contents = "name,calories,price,carbs,fats,proteins\n\
+                        rice,350,5,77,1,7\n\
+                        \n\
+                        eggs,many,10,1,11,13\n";
+        assert_eq!(parse_line(parse_csv(contents)), Some(4));
+    }
+
+    #[test]
+    fn json_records_start_on_their_opening_brace() {
+        let contents = r#"[
+  {"name": "rice", "calories": 350, "price": 5, "carbs": 77, "fats": 1, "proteins": 7},
+
+  {
+    "name": "eggs", "calories": 155, "price": 10, "carbs": 1, "fats": 11, "proteins": 13
+  }
+]"#;
+        assert_eq!(lines(parse_json(contents).unwrap()), vec![(2, "rice".into()), (4, "eggs".into())]);
+    }
+
+    #[test]
+    fn json_errors_inside_a_record_are_offset_by_its_line() {
+        let contents = r#"[
+  {"name": "rice", "calories": 350, "price": 5, "carbs": 77, "fats": 1, "proteins": 7},
+  {
+    "name": "eggs",
+    "calories": "many", "price": 10, "carbs": 1, "fats": 11, "proteins": 13
+  }
+]"#;
+        assert_eq!(parse_line(parse_json(contents)), Some(5));
+        assert_eq!(parse_line(parse_json("[\n  {\"name\": \"rice\",\n")), Some(3));
+    }
+
+    #[test]
+    fn toml_records_start_on_their_table_header() {
+        let contents = "[[dish]]\n\
+                        name = \"rice\"\n\
+                        calories = 350\nprice = 5\ncarbs = 77\nfats = 1\nproteins = 7\n\
+                        \n\
+                        [[dish]]\n\
+                        name = \"eggs\"\n\
+                        calories = 155\nprice = 10\ncarbs = 1\nfats = 11\nproteins = 13\n";
+        assert_eq!(lines(parse_toml(contents).unwrap()), vec![(1, "rice".into()), (9, "eggs".into())]);
+    }
+
+    #[test]
+    fn toml_errors_report_the_line_of_the_bad_value() {
+        let contents = "[[dish]]\nname = \"rice\"\ncalories = \"many\"\n";
+        assert_eq!(parse_line(parse_toml(contents)), Some(3));
+    }
+
+    #[test]
+    fn invalid_dishes_report_the_line_they_start_on() {
+        let contents = "name,calories,price,carbs,fats,proteins\n\
+                        rice,350,5,77,1,7\n\
+                        \n\
+                        rice,155,10,1,11,13\n";
+        match parse_catalog(contents, CatalogFormat::Csv) {
+            Err(CatalogError::Invalid { line, .. }) => assert_eq!(line, 4),
+            other => panic!("expected an invalid dish, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,613 @@
+use std::error::Error;
+use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use crate::aco_config::{AcoConfig, ConfigError, DepositRule, StagnationPolicy};
+use crate::ant_colony::{AcoVariant, BestAnt, Heuristic, Replacement};
+use crate::catalog::load_catalog;
+use crate::dish::{Dish, get_dishes};
+use crate::ga_config::{CrossoverConfig, GaConfig, MutationConfig, ReinsertionConfig, SelectorConfig};
+use crate::multi_colony::{ColonyParams, Migration};
+use crate::pheromone::PheromoneModel;
+use crate::problem::DietProblem;
+
+/// Diet planning with a genetic algorithm and an ant colony
+#[derive(Debug, Parser)]
+#[command(name = "diet", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the genevo based genetic algorithm once
+    Ga(GaArgs),
+    /// Build a diet greedily, the baseline for the other solvers
+    Greedy(GreedyArgs),
+    /// Run the ant colony once
+    Aco(AcoArgs),
+    /// Run several differently tuned colonies that exchange their findings
+    Multi(MultiArgs),
+    /// Run a solver over a grid of colony (or population) sizes and iteration counts
+    Sweep(SweepArgs),
+    /// Plot the convergence of results saved with `--format json`
+    Plot(PlotArgs),
+    /// Measure how many ant paths the colony constructs per second
+    Bench(BenchArgs),
+}
+
+/// Which catalog to plan with and what the diet has to satisfy
+#[derive(Debug, Args)]
+pub struct ProblemArgs {
+    /// Dish catalog (.csv, .json or .toml); the built-in list is used when omitted
+    #[arg(long)]
+    pub catalog: Option<PathBuf>,
+    /// Daily calorie target
+    #[arg(long)]
+    pub calories: Option<u32>,
+    /// Daily carbohydrate target in grams
+    #[arg(long)]
+    pub carbs: Option<u32>,
+    /// Daily fat target in grams
+    #[arg(long)]
+    pub fats: Option<u32>,
+    /// Daily protein target in grams
+    #[arg(long)]
+    pub proteins: Option<u32>,
+    /// Price limit in zloty * 10
+    #[arg(long)]
+    pub budget: Option<u32>,
+    /// Maximum servings of a single dish
+    #[arg(long)]
+    pub max_servings: Option<u32>,
+}
+
+impl ProblemArgs {
+    /// The catalog and the problem, failing when either is unusable
+    pub fn load(&self) -> Result<(Vec<Dish>, DietProblem), Box<dyn Error>> {
+        let dishes = match &self.catalog {
+            Some(path) => load_catalog(path)?,
+            None => get_dishes(),
+        };
+
+        let mut problem = DietProblem::default();
+        let targets = &mut problem.targets;
+        targets.calories = self.calories.unwrap_or(targets.calories);
+        targets.carbs = self.carbs.unwrap_or(targets.carbs);
+        targets.fats = self.fats.unwrap_or(targets.fats);
+        targets.proteins = self.proteins.unwrap_or(targets.proteins);
+        problem.budget = self.budget.unwrap_or(problem.budget);
+        problem.max_servings = self.max_servings.unwrap_or(problem.max_servings);
+        problem.validate()?;
+
+        Ok((dishes, problem))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable summary of the best diet
+    Report,
+    /// The full `RunResult` as JSON
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+    /// How the result is printed
+    #[arg(long, value_enum, default_value_t = OutputFormat::Report)]
+    pub format: OutputFormat,
+    /// Directory to save the result JSON and plots into
+    #[arg(long)]
+    pub output_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct GreedyArgs {
+    #[command(flatten)]
+    pub problem: ProblemArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct GaArgs {
+    #[command(flatten)]
+    pub problem: ProblemArgs,
+    #[command(flatten)]
+    pub evolution: EvolutionArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+/// Genetic algorithm parameters, read from `--config` when given and overridden by the flags
+#[derive(Debug, Args)]
+pub struct EvolutionArgs {
+    /// TOML file with a `GaConfig`, the flags below take precedence over it
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Number of individuals in the population [default: 15]
+    #[arg(long)]
+    pub population: Option<usize>,
+    /// Number of generations [default: 1000]
+    #[arg(long)]
+    pub iterations: Option<usize>,
+    /// How parents are picked [default: tournament]
+    #[arg(long, value_enum)]
+    pub selector: Option<SelectorKind>,
+    /// Share of the population picked as parents in each generation [default: 0.85]
+    #[arg(long)]
+    pub selection_ratio: Option<f64>,
+    /// Individuals taking part in each tournament [default: 3]
+    #[arg(long)]
+    pub tournament_size: Option<usize>,
+    /// How children are bred [default: single-point]
+    #[arg(long, value_enum)]
+    pub crossover: Option<CrossoverKind>,
+    /// Cut points of the multi point crossover [default: 2]
+    #[arg(long)]
+    pub cut_points: Option<usize>,
+    /// How far the blend crossover reaches beyond the parents' genes [default: 0.5]
+    #[arg(long)]
+    pub blend_alpha: Option<f64>,
+    /// How children mutate [default: inversion]
+    #[arg(long, value_enum)]
+    pub mutation: Option<MutationKind>,
+    /// Probability of a child mutating, or of each gene creeping [default: 0.1, creep 0.05]
+    #[arg(long)]
+    pub mutation_rate: Option<f64>,
+    /// Most servings a gene creeps by [default: 1]
+    #[arg(long)]
+    pub creep_step: Option<u32>,
+    /// Draw the creep from a normal distribution with this deviation instead
+    #[arg(long)]
+    pub creep_sigma: Option<f64>,
+    /// Share of the children repaired towards a feasible diet after mutating [default: 0]
+    #[arg(long)]
+    pub repair_rate: Option<f64>,
+    /// How children make it into the next generation [default: elitist]
+    #[arg(long, value_enum)]
+    pub reinsertion: Option<ReinsertionKind>,
+    /// Share of the population replaced by children in each generation [default: 0.85]
+    #[arg(long)]
+    pub replace_ratio: Option<f64>,
+    /// Fewest servings of a dish a gene of the initial population holds [default: 0]
+    #[arg(long)]
+    pub gene_min: Option<u32>,
+    /// Most servings of a dish a gene of the initial population holds [default: the problem's cap]
+    #[arg(long)]
+    pub gene_max: Option<u32>,
+    /// Seed for a reproducible run, a random one is used otherwise
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SelectorKind {
+    /// The fittest individuals become parents
+    Maximize,
+    /// Parents are drawn proportionally to their fitness
+    Roulette,
+    /// The fittest of a few random individuals becomes a parent
+    Tournament,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum CrossoverKind {
+    /// Genes are swapped after one random cut point
+    SinglePoint,
+    /// Genes are swapped between several random cut points
+    MultiPoint,
+    /// Every gene comes from a random parent
+    Uniform,
+    /// Genes are drawn around the range spanned by two parents
+    Blend,
+    /// Children are weighted averages of two parents
+    Arithmetic,
+    /// Dishes come from the parent that brings the child closer to the targets
+    Nutrient,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum MutationKind {
+    /// A random segment of the genome is reversed
+    Inversion,
+    /// Single dishes gain or lose a few servings
+    Creep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ReinsertionKind {
+    /// The fittest children replace the least fit individuals
+    Elitist,
+    /// Random children replace random individuals
+    Uniform,
+}
+
+impl EvolutionArgs {
+    pub fn config(&self) -> Result<GaConfig, ConfigError> {
+        let mut config = match &self.config {
+            Some(path) => GaConfig::load(path)?,
+            None => GaConfig::default(),
+        };
+        config.population = self.population.unwrap_or(config.population);
+        config.generations = self.iterations.unwrap_or(config.generations);
+        config.repair_rate = self.repair_rate.unwrap_or(config.repair_rate);
+        config.min_servings = self.gene_min.unwrap_or(config.min_servings);
+        config.max_servings = self.gene_max.or(config.max_servings);
+        config.seed = self.seed.or(config.seed);
+
+        if let Some(kind) = self.selector {
+            config.selector = match kind {
+                SelectorKind::Maximize => SelectorConfig::maximize(),
+                SelectorKind::Roulette => SelectorConfig::roulette(),
+                SelectorKind::Tournament => SelectorConfig::tournament(),
+            };
+        }
+        if let Some(kind) = self.crossover {
+            config.crossover = match kind {
+                CrossoverKind::SinglePoint => CrossoverConfig::SinglePoint,
+                CrossoverKind::MultiPoint => CrossoverConfig::multi_point(),
+                CrossoverKind::Uniform => CrossoverConfig::Uniform,
+                CrossoverKind::Blend => CrossoverConfig::blend(),
+                CrossoverKind::Arithmetic => CrossoverConfig::Arithmetic,
+                CrossoverKind::Nutrient => CrossoverConfig::Nutrient,
+            };
+        }
+        if let Some(kind) = self.mutation {
+            config.mutation = match kind {
+                MutationKind::Inversion => MutationConfig::inversion(),
+                MutationKind::Creep => MutationConfig::creep(),
+            };
+        }
+        if let Some(kind) = self.reinsertion {
+            config.reinsertion = match kind {
+                ReinsertionKind::Elitist => ReinsertionConfig::elitist(),
+                ReinsertionKind::Uniform => ReinsertionConfig::uniform(),
+            };
+        }
+        match &mut config.selector {
+            SelectorConfig::Maximize { selection_ratio, .. } | SelectorConfig::Roulette { selection_ratio, .. } => {
+                *selection_ratio = self.selection_ratio.unwrap_or(*selection_ratio);
+            }
+            SelectorConfig::Tournament { selection_ratio, tournament_size, .. } => {
+                *selection_ratio = self.selection_ratio.unwrap_or(*selection_ratio);
+                *tournament_size = self.tournament_size.unwrap_or(*tournament_size);
+            }
+        }
+        match &mut config.crossover {
+            CrossoverConfig::MultiPoint { cut_points } => *cut_points = self.cut_points.unwrap_or(*cut_points),
+            CrossoverConfig::Blend { alpha } => *alpha = self.blend_alpha.unwrap_or(*alpha),
+            CrossoverConfig::SinglePoint | CrossoverConfig::Uniform | CrossoverConfig::Arithmetic | CrossoverConfig::Nutrient => {}
+        }
+        match &mut config.mutation {
+            MutationConfig::Inversion { rate } => *rate = self.mutation_rate.unwrap_or(*rate),
+            MutationConfig::Creep { rate, max_step, sigma } => {
+                *rate = self.mutation_rate.unwrap_or(*rate);
+                *max_step = self.creep_step.unwrap_or(*max_step);
+                *sigma = self.creep_sigma.or(*sigma);
+            }
+        }
+        match &mut config.reinsertion {
+            ReinsertionConfig::Elitist { replace_ratio, .. } | ReinsertionConfig::Uniform { replace_ratio } => {
+                *replace_ratio = self.replace_ratio.unwrap_or(*replace_ratio);
+            }
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Colony parameters, read from `--config` when given and overridden by the flags
+#[derive(Debug, Args)]
+pub struct ColonyArgs {
+    /// TOML file with an `AcoConfig`, the flags below take precedence over it
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Number of ants in the colony [default: 1000]
+    #[arg(long)]
+    pub ants: Option<usize>,
+    /// Number of colony iterations [default: 5000]
+    #[arg(long)]
+    pub iterations: Option<usize>,
+    /// Share of the pheromone evaporating in each iteration [default: 0.1]
+    #[arg(long)]
+    pub evaporation: Option<f64>,
+    /// Ant System: which ants deposit pheromone [default: fitness-ratio]
+    #[arg(long, value_enum)]
+    pub deposit: Option<DepositKind>,
+    /// Rank based deposit: how many ants are ranked, the best so far path included [default: 6]
+    #[arg(long)]
+    pub rank_ants: Option<usize>,
+    /// Elitist deposit: weight of the best so far path [default: 5.0]
+    #[arg(long)]
+    pub elite_weight: Option<f64>,
+    /// Start the trails from the greedy diet, which also becomes the baseline of the report
+    #[arg(long)]
+    pub greedy_init: bool,
+    /// Extra pheromone on the trails of the greedy diet, as a multiple of the starting level [default: 0]
+    #[arg(long)]
+    pub greedy_bias: Option<f64>,
+    /// Servings of a single dish an ant may take [default: the problem's cap]
+    #[arg(long)]
+    pub ant_max_servings: Option<u32>,
+    /// Steps an ant may take before its path ends [default: every serving of every dish]
+    #[arg(long)]
+    pub max_path_length: Option<usize>,
+    /// Improve every ant's path with ±1 serving changes, swaps and drops before the pheromone update
+    #[arg(long)]
+    pub local_search: bool,
+    /// Snapshot the pheromone every this many iterations, saved as CSV, heatmap and GIF into `--output-dir`
+    #[arg(long)]
+    pub snapshot_every: Option<usize>,
+    /// What to do once the search stagnated [default: ignore]
+    #[arg(long, value_enum)]
+    pub stagnation: Option<StagnationPolicy>,
+    /// The search stagnated once the ants build at most this many distinct diets [default: 1]
+    #[arg(long)]
+    pub stagnation_paths: Option<usize>,
+    /// ... or once the average lambda-branching factor of the pheromone drops to this value
+    #[arg(long)]
+    pub stagnation_branching: Option<f64>,
+    /// Heuristic information combined with the pheromone when choosing dishes [default: none]
+    #[arg(long, value_enum)]
+    pub heuristic: Option<Heuristic>,
+    /// Exponent of the pheromone in the dish choice [default: 1.0]
+    #[arg(long)]
+    pub alpha: Option<f64>,
+    /// Exponent of the heuristic in the dish choice [default: 2.0]
+    #[arg(long)]
+    pub beta: Option<f64>,
+    /// What the pheromone trails are attached to [default: dish]
+    #[arg(long, value_enum)]
+    pub pheromone: Option<PheromoneModel>,
+    /// How the colony updates its pheromone trails [default: ant-system]
+    #[arg(long, value_enum)]
+    pub variant: Option<VariantKind>,
+    /// MAX-MIN: which ant deposits pheromone [default: iteration-best]
+    #[arg(long, value_enum)]
+    pub best_ant: Option<BestAnt>,
+    /// MAX-MIN: probability of rebuilding the best path on converged trails, sets tau_min [default: 0.05]
+    #[arg(long)]
+    pub p_best: Option<f64>,
+    /// MAX-MIN: iterations without improvement before the trails are reinitialised [default: 50]
+    #[arg(long)]
+    pub stagnation_limit: Option<usize>,
+    /// ACS: probability of greedily taking the most desirable dish [default: 0.9]
+    #[arg(long)]
+    pub q0: Option<f64>,
+    /// ACS: strength of the local pheromone decay [default: 0.1]
+    #[arg(long)]
+    pub xi: Option<f64>,
+    /// P-ACO: paths kept in the archive [default: 5]
+    #[arg(long)]
+    pub archive_size: Option<usize>,
+    /// P-ACO: which path leaves the full archive [default: age]
+    #[arg(long, value_enum)]
+    pub replacement: Option<Replacement>,
+    /// P-ACO: pheromone an archived path adds, as a multiple of the initial level [default: 1.0]
+    #[arg(long)]
+    pub archive_weight: Option<f64>,
+    /// Seed for a reproducible run, a random one is used otherwise
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum VariantKind {
+    /// Ant System, the original update scheme
+    AntSystem,
+    /// MAX-MIN Ant System
+    MaxMin,
+    /// Ant Colony System
+    ColonySystem,
+    /// Population-based ACO
+    PopulationBased,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum DepositKind {
+    /// Ants deposit with a probability given by their quality relative to the iteration best
+    FitnessRatio,
+    /// Every ant deposits
+    AllAnts,
+    /// The best ants deposit weighted by their rank, together with the best so far path
+    RankBased,
+    /// Every ant deposits and the best so far path deposits extra
+    Elitist,
+    /// Only the iteration best ant deposits
+    IterationBest,
+}
+
+impl ColonyArgs {
+    pub fn config(&self) -> Result<AcoConfig, ConfigError> {
+        let mut config = match &self.config {
+            Some(path) => AcoConfig::load(path)?,
+            None => AcoConfig::default(),
+        };
+        config.ants = self.ants.unwrap_or(config.ants);
+        config.iterations = self.iterations.unwrap_or(config.iterations);
+        config.evaporation = self.evaporation.unwrap_or(config.evaporation);
+        config.max_servings = self.ant_max_servings.or(config.max_servings);
+        config.max_path_length = self.max_path_length.or(config.max_path_length);
+        config.local_search |= self.local_search;
+        config.greedy_init |= self.greedy_init;
+        config.greedy_bias = self.greedy_bias.unwrap_or(config.greedy_bias);
+        config.snapshot_every = self.snapshot_every.or(config.snapshot_every);
+        config.stagnation = self.stagnation.unwrap_or(config.stagnation);
+        config.stagnation_paths = self.stagnation_paths.unwrap_or(config.stagnation_paths);
+        config.stagnation_branching = self.stagnation_branching.or(config.stagnation_branching);
+        config.heuristic = self.heuristic.unwrap_or(config.heuristic);
+        config.alpha = self.alpha.unwrap_or(config.alpha);
+        config.beta = self.beta.unwrap_or(config.beta);
+        config.pheromone = self.pheromone.unwrap_or(config.pheromone);
+        config.seed = self.seed.or(config.seed);
+
+        if let Some(kind) = self.variant {
+            config.variant = match kind {
+                VariantKind::AntSystem => AcoVariant::AntSystem,
+                VariantKind::MaxMin => AcoVariant::max_min(),
+                VariantKind::ColonySystem => AcoVariant::colony_system(),
+                VariantKind::PopulationBased => AcoVariant::population_based(),
+            };
+        }
+        if let Some(kind) = self.deposit {
+            config.deposit = match kind {
+                DepositKind::FitnessRatio => DepositRule::FitnessRatio,
+                DepositKind::AllAnts => DepositRule::AllAnts,
+                DepositKind::RankBased => DepositRule::rank_based(),
+                DepositKind::Elitist => DepositRule::elitist(),
+                DepositKind::IterationBest => DepositRule::IterationBest,
+            };
+        }
+        match &mut config.deposit {
+            DepositRule::RankBased { ants } => *ants = self.rank_ants.unwrap_or(*ants),
+            DepositRule::Elitist { weight } => *weight = self.elite_weight.unwrap_or(*weight),
+            DepositRule::FitnessRatio | DepositRule::AllAnts | DepositRule::IterationBest => {}
+        }
+        match &mut config.variant {
+            AcoVariant::AntSystem => {}
+            AcoVariant::MaxMin { best_ant, p_best, stagnation_limit } => {
+                *best_ant = self.best_ant.unwrap_or(*best_ant);
+                *p_best = self.p_best.unwrap_or(*p_best);
+                *stagnation_limit = self.stagnation_limit.unwrap_or(*stagnation_limit);
+            }
+            AcoVariant::ColonySystem { q0, xi } => {
+                *q0 = self.q0.unwrap_or(*q0);
+                *xi = self.xi.unwrap_or(*xi);
+            }
+            AcoVariant::PopulationBased { archive_size, replacement, weight } => {
+                *archive_size = self.archive_size.unwrap_or(*archive_size);
+                *replacement = self.replacement.unwrap_or(*replacement);
+                *weight = self.archive_weight.unwrap_or(*weight);
+            }
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct AcoArgs {
+    #[command(flatten)]
+    pub problem: ProblemArgs,
+    #[command(flatten)]
+    pub colony: ColonyArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum MigrationKind {
+    /// Colonies receive the best path of their neighbour
+    BestPath,
+    /// Colonies blend their trails with their neighbour's
+    BlendTrails,
+}
+
+#[derive(Debug, Args)]
+pub struct MultiArgs {
+    #[command(flatten)]
+    pub problem: ProblemArgs,
+    /// Settings shared by every colony
+    #[command(flatten)]
+    pub colony: ColonyArgs,
+    /// Alpha of each colony, one colony per value; replaces `--alpha`
+    #[arg(long, value_delimiter = ',', default_value = "1,1,2,0.5")]
+    pub alphas: Vec<f64>,
+    /// Beta of each colony, as many values as `--alphas`; replaces `--beta`
+    #[arg(long, value_delimiter = ',', default_value = "2,4,1,2")]
+    pub betas: Vec<f64>,
+    /// Evaporation of each colony, as many values as `--alphas`; replaces `--evaporation`
+    #[arg(long, value_delimiter = ',', default_value = "0.1,0.05,0.2,0.1")]
+    pub evaporations: Vec<f64>,
+    /// Iterations between two migrations
+    #[arg(long, default_value_t = 25)]
+    pub migration_interval: usize,
+    #[arg(long, value_enum, default_value_t = MigrationKind::BestPath)]
+    pub migration: MigrationKind,
+    /// Blend trails: share of the way a colony's trails move towards its neighbour's
+    #[arg(long, default_value_t = 0.1)]
+    pub blend_weight: f64,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+impl MultiArgs {
+    pub fn colonies(&self) -> Result<Vec<ColonyParams>, ConfigError> {
+        if self.betas.len() != self.alphas.len() || self.evaporations.len() != self.alphas.len() {
+            return Err(ConfigError::Invalid("--alphas, --betas and --evaporations need one value per colony".into()));
+        }
+        Ok(self.alphas.iter()
+            .zip(&self.betas)
+            .zip(&self.evaporations)
+            .map(|((&alpha, &beta), &evaporation)| ColonyParams { alpha, beta, evaporation })
+            .collect())
+    }
+
+    pub fn migration(&self) -> Migration {
+        match self.migration {
+            MigrationKind::BestPath => Migration::BestPath,
+            MigrationKind::BlendTrails => Migration::BlendTrails { weight: self.blend_weight },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Algorithm {
+    Ga,
+    Aco,
+}
+
+#[derive(Debug, Args)]
+pub struct SweepArgs {
+    #[command(flatten)]
+    pub problem: ProblemArgs,
+    #[arg(long, value_enum, default_value_t = Algorithm::Aco)]
+    pub algorithm: Algorithm,
+    /// Colony sizes to try
+    #[arg(long, value_delimiter = ',', default_value = "5,10,20,40,80,160,320,640,1280,2560,5120")]
+    pub ants: Vec<usize>,
+    /// Population size used by the genetic algorithm [default: 15]
+    #[arg(long)]
+    pub population: Option<usize>,
+    /// Iteration (or generation) counts to try
+    #[arg(long, value_delimiter = ',', default_value = "5,10,20,40,80,160,320,640,1280,2560,5120")]
+    pub iterations: Vec<usize>,
+    /// How many runs the genetic algorithm averages per generation count
+    #[arg(long, default_value_t = 5)]
+    pub repeats: usize,
+    /// TOML file with the `AcoConfig` (or `GaConfig`) every run of the sweep starts from
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Seed for a reproducible sweep
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Directory the sweep plots are written into
+    #[arg(long, default_value = ".")]
+    pub output_dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct PlotArgs {
+    /// Result files written by `ga` or `aco` with `--format json`
+    #[arg(required = true)]
+    pub results: Vec<PathBuf>,
+    /// Directory the plot is written into
+    #[arg(long, default_value = ".")]
+    pub output_dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub problem: ProblemArgs,
+    /// Replace the catalog with this many random foods, generated from the seed
+    #[arg(long)]
+    pub synthetic_dishes: Option<usize>,
+    /// Runs 20 iterations unless `--iterations` is given
+    #[command(flatten)]
+    pub colony: ColonyArgs,
+}
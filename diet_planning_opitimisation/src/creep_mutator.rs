@@ -0,0 +1,94 @@
+use genevo::operator::{GeneticOperator, MutationOp};
+use genevo::prelude::Rng;
+
+/// Nudges single genes of a count genotype up or down, keeping every gene in
+/// `[0, max_value]`. A step against the bound a gene sits on is taken in the other
+/// direction, so a mutated gene always moves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreepMutator {
+    /// Probability of each gene to creep
+    pub mutation_rate: f64,
+    /// A gene moves by 1 to `max_step`, drawn uniformly
+    pub max_step: u32,
+    /// Draws the step from a normal distribution with this deviation instead, rounded
+    /// away from zero so the gene always moves
+    pub sigma: Option<f64>,
+    pub max_value: u32,
+}
+
+impl CreepMutator {
+    fn step<R>(&self, rng: &mut R) -> i64
+        where
+            R: Rng + Sized,
+    {
+        match self.sigma {
+            Some(sigma) => {
+                // Box-Muller, rand_distr isn't worth a dependency for a single draw
+                let (u1, u2): (f64, f64) = (1.0 - rng.gen::<f64>(), rng.gen());
+                let normal = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                let step = (sigma * normal).abs().round().max(1.0) as i64;
+                if normal < 0.0 { -step } else { step }
+            }
+            None => {
+                let step = rng.gen_range(1..=self.max_step) as i64;
+                if rng.gen() { -step } else { step }
+            }
+        }
+    }
+}
+
+impl GeneticOperator for CreepMutator {
+    fn name() -> String {
+        "Creep-Mutation".to_string()
+    }
+}
+
+impl MutationOp<Vec<u32>> for CreepMutator {
+    fn mutate<R>(&self, mut genome: Vec<u32>, rng: &mut R) -> Vec<u32>
+        where
+            R: Rng + Sized,
+    {
+        let max_value = self.max_value as i64;
+        for gene in &mut genome {
+            if rng.gen::<f64>() < self.mutation_rate {
+                let (step, start) = (self.step(rng), *gene as i64);
+                let mut value = (start + step).clamp(0, max_value);
+                if value == start {
+                    value = (start - step).clamp(0, max_value);
+                }
+                *gene = value as u32;
+            }
+        }
+        genome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use super::*;
+
+    const MAX_VALUE: u32 = 10;
+
+    fn assert_moves_within_bounds(mutator: CreepMutator) {
+        let mut rng = StdRng::seed_from_u64(7);
+        for start in [0, MAX_VALUE] {
+            for _ in 0..100 {
+                let mutated = mutator.mutate(vec![start; 20], &mut rng);
+                assert!(mutated.iter().all(|&gene| gene <= MAX_VALUE && gene != start), "{} became {:?}", start, mutated);
+            }
+        }
+    }
+
+    #[test]
+    fn uniform_steps_move_every_gene_within_bounds() {
+        assert_moves_within_bounds(CreepMutator { mutation_rate: 1.0, max_step: 3, sigma: None, max_value: MAX_VALUE });
+    }
+
+    #[test]
+    fn gaussian_steps_move_every_gene_within_bounds() {
+        // wide enough that steps regularly overshoot the whole range
+        assert_moves_within_bounds(CreepMutator { mutation_rate: 1.0, max_step: 1, sigma: Some(8.0), max_value: MAX_VALUE });
+    }
+}
@@ -1,4 +1,8 @@
-#[derive(Debug, Clone)]
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dish {
     pub name: String,
     pub calories: u32,
@@ -132,4 +136,20 @@ pub fn get_dishes() -> Vec<Dish> {
             proteins: 0,
         },
     ]
-}
\ No newline at end of file
+}
+
+/// A random catalog of `count` foods with nutrients in the range of the built-in
+/// dishes, for benchmarking on catalogs far larger than the real one
+pub fn synthetic_dishes(count: usize, seed: u64) -> Vec<Dish> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|i| Dish {
+            name: format!("synthetic food {}", i + 1),
+            calories: rng.gen_range(10..400),
+            price: rng.gen_range(1..40),
+            carbs: rng.gen_range(0..60),
+            fats: rng.gen_range(0..30),
+            proteins: rng.gen_range(0..30),
+        })
+        .collect()
+}
@@ -0,0 +1,121 @@
+use genevo::algorithm::EvaluatedPopulation;
+use genevo::genetic::{Children, Offspring, Parents};
+use genevo::operator::{CrossoverOp, GeneticOperator, MultiObjective, MutationOp, ReinsertionOp, SingleObjective};
+use genevo::operator::prelude::{ElitistReinserter, MultiPointCrossBreeder, SinglePointCrossBreeder, UniformCrossBreeder, UniformReinserter};
+use genevo::prelude::{*};
+use crate::blend_crossover::{ArithmeticCrossover, BlendCrossover};
+use crate::creep_mutator::CreepMutator;
+use crate::inversion_mutator::InversionMutator;
+use crate::nutrient_crossover::NutrientCrossover;
+
+/// A crossover picked at runtime, see `CrossoverConfig`
+#[derive(Clone, Debug)]
+pub enum DynamicCrossover<'a> {
+    SinglePoint(SinglePointCrossBreeder),
+    MultiPoint(MultiPointCrossBreeder),
+    Uniform(UniformCrossBreeder),
+    Blend(BlendCrossover),
+    Arithmetic(ArithmeticCrossover),
+    Nutrient(NutrientCrossover<'a>),
+}
+
+impl GeneticOperator for DynamicCrossover<'_> {
+    fn name() -> String {
+        "variable_crossover".to_string()
+    }
+}
+
+impl CrossoverOp<Vec<u32>> for DynamicCrossover<'_> {
+    fn crossover<R>(&self, parents: Parents<Vec<u32>>, rng: &mut R) -> Children<Vec<u32>>
+        where
+            R: Rng + Sized,
+    {
+        match self {
+            DynamicCrossover::SinglePoint(crossover) => crossover.crossover(parents, rng),
+            DynamicCrossover::MultiPoint(crossover) => crossover.crossover(parents, rng),
+            DynamicCrossover::Uniform(crossover) => crossover.crossover(parents, rng),
+            DynamicCrossover::Blend(crossover) => crossover.crossover(parents, rng),
+            DynamicCrossover::Arithmetic(crossover) => crossover.crossover(parents, rng),
+            DynamicCrossover::Nutrient(crossover) => crossover.crossover(parents, rng),
+        }
+    }
+}
+
+/// A mutation picked at runtime, see `MutationConfig`
+#[derive(Clone, Debug, PartialEq)]
+pub enum DynamicMutator {
+    Inversion(InversionMutator),
+    Creep(CreepMutator),
+}
+
+impl GeneticOperator for DynamicMutator {
+    fn name() -> String {
+        "variable_mutator".to_string()
+    }
+}
+
+impl MutationOp<Vec<u32>> for DynamicMutator {
+    fn mutate<R>(&self, genome: Vec<u32>, rng: &mut R) -> Vec<u32>
+        where
+            R: Rng + Sized,
+    {
+        match self {
+            DynamicMutator::Inversion(mutator) => mutator.mutate(genome, rng),
+            DynamicMutator::Creep(mutator) => mutator.mutate(genome, rng),
+        }
+    }
+}
+
+/// A reinsertion picked at runtime, see `ReinsertionConfig`
+#[derive(Clone, Debug, PartialEq)]
+pub enum DynamicReinserter<G, F, E>
+    where
+        G: Genotype,
+        F: Fitness,
+        E: FitnessFunction<G, F>,
+{
+    Elitist(ElitistReinserter<G, F, E>),
+    Uniform(UniformReinserter),
+}
+
+impl<G, F, E> SingleObjective for DynamicReinserter<G, F, E>
+    where
+        G: Genotype,
+        F: Fitness,
+        E: FitnessFunction<G, F>,
+{}
+
+impl<G, F, E> MultiObjective for DynamicReinserter<G, F, E>
+    where
+        G: Genotype,
+        F: Fitness,
+        E: FitnessFunction<G, F>,
+{}
+
+impl<G, F, E> GeneticOperator for DynamicReinserter<G, F, E>
+    where
+        G: Genotype,
+        F: Fitness,
+        E: FitnessFunction<G, F>,
+{
+    fn name() -> String {
+        "variable_reinserter".to_string()
+    }
+}
+
+impl<G, F, E> ReinsertionOp<G, F> for DynamicReinserter<G, F, E>
+    where
+        G: Genotype,
+        F: Fitness,
+        E: FitnessFunction<G, F>,
+{
+    fn combine<R>(&self, offspring: &mut Offspring<G>, population: &EvaluatedPopulation<G, F>, rng: &mut R) -> Vec<G>
+        where
+            R: Rng + Sized,
+    {
+        match self {
+            DynamicReinserter::Elitist(reinserter) => reinserter.combine(offspring, population, rng),
+            DynamicReinserter::Uniform(reinserter) => reinserter.combine(offspring, population, rng),
+        }
+    }
+}
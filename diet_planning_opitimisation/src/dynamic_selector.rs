@@ -3,6 +3,7 @@ use genevo::genetic::{AsScalar, Parents};
 use genevo::operator::{GeneticOperator, MultiObjective, SelectionOp, SingleObjective};
 use genevo::operator::prelude::{MaximizeSelector, RouletteWheelSelector, TournamentSelector};
 use genevo::prelude::{*};
+use crate::sampling::sample_cumulative;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum GenevoSelector {
@@ -43,8 +44,36 @@ impl<G, F> SelectionOp<G, F> for DynamicSelector
     {
         match &self.selector {
             GenevoSelector::Maximize(selector) => selector.select_from(evaluated, rng),
-            GenevoSelector::Roulette(selector) => selector.select_from(evaluated, rng),
+            GenevoSelector::Roulette(selector) => spin_roulette(selector, evaluated, rng),
             GenevoSelector::Tournament(selector) => selector.select_from(evaluated, rng)
         }
     }
 }
+
+/// genevo's roulette wheel can't weigh negative fitness, which penalised diets have,
+/// so the wheel weighs every individual by how much fitter it is than the least fit one
+fn spin_roulette<G, F, R>(selector: &RouletteWheelSelector, evaluated: &EvaluatedPopulation<G, F>, rng: &mut R) -> Vec<Parents<G>>
+    where
+        G: Genotype,
+        F: Fitness + AsScalar,
+        R: Rng + Sized,
+{
+    let individuals = evaluated.individuals();
+    let fitness_values: Vec<f64> = evaluated.fitness_values().iter().map(AsScalar::as_scalar).collect();
+    let least_fit = fitness_values.iter().cloned().fold(f64::MAX, f64::min);
+    // the extra 1 keeps the least fit individual on the wheel
+    let cumulative: Vec<f64> = fitness_values.iter()
+        .scan(0.0, |sum, fitness| {
+            *sum += fitness - least_fit + 1.0;
+            Some(*sum)
+        })
+        .collect();
+    let parent_count = (individuals.len() as f64 * selector.selection_ratio() + 0.5).floor() as usize;
+    (0..parent_count)
+        .map(|_| {
+            (0..selector.num_individuals_per_parents())
+                .map(|_| individuals[sample_cumulative(&cumulative, rng).unwrap()].clone())
+                .collect()
+        })
+        .collect()
+}
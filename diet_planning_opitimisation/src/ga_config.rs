@@ -0,0 +1,383 @@
+use std::fs;
+use std::path::Path;
+use genevo::operator::prelude::{ElitistReinserter, MaximizeSelector, MultiPointCrossBreeder, RouletteWheelSelector, SinglePointCrossBreeder, TournamentSelector, UniformCrossBreeder, UniformReinserter};
+use genevo::prelude::{Fitness, FitnessFunction, Genotype};
+use serde::{Deserialize, Serialize};
+use crate::aco_config::{check, ConfigError};
+use crate::blend_crossover::{ArithmeticCrossover, BlendCrossover};
+use crate::dish::Dish;
+use crate::dynamic_operators::{DynamicCrossover, DynamicMutator, DynamicReinserter};
+use crate::dynamic_selector::{DynamicSelector, GenevoSelector};
+use crate::creep_mutator::CreepMutator;
+use crate::inversion_mutator::InversionMutator;
+use crate::nutrient_crossover::NutrientCrossover;
+use crate::problem::DietProblem;
+
+/// How the parents of the next generation are picked
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum SelectorConfig {
+    /// The fittest individuals become parents
+    Maximize {
+        #[serde(default = "default_selection_ratio")]
+        selection_ratio: f64,
+        #[serde(default = "default_individuals_per_parent")]
+        individuals_per_parent: usize,
+    },
+    /// Individuals become parents with a probability proportional to their fitness
+    Roulette {
+        #[serde(default = "default_selection_ratio")]
+        selection_ratio: f64,
+        #[serde(default = "default_individuals_per_parent")]
+        individuals_per_parent: usize,
+    },
+    /// The fittest of `tournament_size` random individuals wins with `probability`,
+    /// otherwise the next fittest gets the same chance
+    Tournament {
+        #[serde(default = "default_selection_ratio")]
+        selection_ratio: f64,
+        #[serde(default = "default_individuals_per_parent")]
+        individuals_per_parent: usize,
+        #[serde(default = "default_tournament_size")]
+        tournament_size: usize,
+        #[serde(default = "default_tournament_probability")]
+        probability: f64,
+        #[serde(default)]
+        remove_selected: bool,
+    },
+}
+
+fn default_selection_ratio() -> f64 {
+    0.85
+}
+
+fn default_individuals_per_parent() -> usize {
+    12
+}
+
+fn default_tournament_size() -> usize {
+    3
+}
+
+fn default_tournament_probability() -> f64 {
+    1.0
+}
+
+impl SelectorConfig {
+    pub fn tournament() -> Self {
+        SelectorConfig::Tournament {
+            selection_ratio: default_selection_ratio(),
+            individuals_per_parent: default_individuals_per_parent(),
+            tournament_size: default_tournament_size(),
+            probability: default_tournament_probability(),
+            remove_selected: false,
+        }
+    }
+
+    pub fn maximize() -> Self {
+        SelectorConfig::Maximize { selection_ratio: default_selection_ratio(), individuals_per_parent: default_individuals_per_parent() }
+    }
+
+    pub fn roulette() -> Self {
+        SelectorConfig::Roulette { selection_ratio: default_selection_ratio(), individuals_per_parent: default_individuals_per_parent() }
+    }
+
+    pub fn operator(&self) -> DynamicSelector {
+        DynamicSelector::new(match *self {
+            SelectorConfig::Maximize { selection_ratio, individuals_per_parent } => {
+                GenevoSelector::Maximize(MaximizeSelector::new(selection_ratio, individuals_per_parent))
+            }
+            SelectorConfig::Roulette { selection_ratio, individuals_per_parent } => {
+                GenevoSelector::Roulette(RouletteWheelSelector::new(selection_ratio, individuals_per_parent))
+            }
+            SelectorConfig::Tournament { selection_ratio, individuals_per_parent, tournament_size, probability, remove_selected } => {
+                GenevoSelector::Tournament(TournamentSelector::new(selection_ratio, individuals_per_parent, tournament_size, probability, remove_selected))
+            }
+        })
+    }
+
+    fn individuals_per_parent(&self) -> usize {
+        match *self {
+            SelectorConfig::Maximize { individuals_per_parent, .. }
+            | SelectorConfig::Roulette { individuals_per_parent, .. }
+            | SelectorConfig::Tournament { individuals_per_parent, .. } => individuals_per_parent,
+        }
+    }
+
+    fn selection_ratio(&self) -> f64 {
+        match *self {
+            SelectorConfig::Maximize { selection_ratio, .. }
+            | SelectorConfig::Roulette { selection_ratio, .. }
+            | SelectorConfig::Tournament { selection_ratio, .. } => selection_ratio,
+        }
+    }
+}
+
+/// How the children are bred from their parents
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum CrossoverConfig {
+    /// Children swap the genes after a single random cut point
+    SinglePoint,
+    /// Children alternate between the parents at `cut_points` random cut points
+    MultiPoint {
+        #[serde(default = "default_cut_points")]
+        cut_points: usize,
+    },
+    /// Every gene of a child comes from a random parent
+    Uniform,
+    /// Every gene is drawn between the genes of two parents, widened by `alpha` of
+    /// their distance on both sides (BLX-alpha)
+    Blend {
+        #[serde(default = "default_blend_alpha")]
+        alpha: f64,
+    },
+    /// Children are weighted averages of two parents
+    Arithmetic,
+    /// Children take each dish from the parent that brings them closer to the nutrient
+    /// targets within the budget
+    Nutrient,
+}
+
+fn default_cut_points() -> usize {
+    2
+}
+
+fn default_blend_alpha() -> f64 {
+    0.5
+}
+
+impl CrossoverConfig {
+    pub fn multi_point() -> Self {
+        CrossoverConfig::MultiPoint { cut_points: default_cut_points() }
+    }
+
+    pub fn blend() -> Self {
+        CrossoverConfig::Blend { alpha: default_blend_alpha() }
+    }
+
+    /// Genes are kept at or below `max_servings`, the nutrient crossover weighs
+    /// `dishes` against `problem`
+    pub fn operator<'a>(&self, dishes: &'a [Dish], problem: &'a DietProblem, max_servings: u32) -> DynamicCrossover<'a> {
+        match *self {
+            CrossoverConfig::SinglePoint => DynamicCrossover::SinglePoint(SinglePointCrossBreeder::new()),
+            CrossoverConfig::MultiPoint { cut_points } => DynamicCrossover::MultiPoint(MultiPointCrossBreeder::new(cut_points)),
+            CrossoverConfig::Uniform => DynamicCrossover::Uniform(UniformCrossBreeder::new()),
+            CrossoverConfig::Blend { alpha } => DynamicCrossover::Blend(BlendCrossover { alpha, max_value: max_servings }),
+            CrossoverConfig::Arithmetic => DynamicCrossover::Arithmetic(ArithmeticCrossover),
+            CrossoverConfig::Nutrient => DynamicCrossover::Nutrient(NutrientCrossover { dishes, problem }),
+        }
+    }
+}
+
+/// How the children mutate
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum MutationConfig {
+    /// A random segment of the genome is reversed with probability `rate`
+    Inversion {
+        #[serde(default = "default_mutation_rate")]
+        rate: f64,
+    },
+    /// Every gene moves up or down by a few servings with probability `rate`, staying
+    /// within the gene bounds
+    Creep {
+        #[serde(default = "default_creep_rate")]
+        rate: f64,
+        /// Steps are drawn uniformly from 1 to `max_step`...
+        #[serde(default = "default_creep_step")]
+        max_step: u32,
+        /// ... or from a normal distribution with this deviation when set
+        #[serde(default)]
+        sigma: Option<f64>,
+    },
+}
+
+fn default_mutation_rate() -> f64 {
+    0.1
+}
+
+fn default_creep_rate() -> f64 {
+    0.05
+}
+
+fn default_creep_step() -> u32 {
+    1
+}
+
+impl MutationConfig {
+    pub fn inversion() -> Self {
+        MutationConfig::Inversion { rate: default_mutation_rate() }
+    }
+
+    pub fn creep() -> Self {
+        MutationConfig::Creep { rate: default_creep_rate(), max_step: default_creep_step(), sigma: None }
+    }
+
+    /// Genes are kept at or below `max_servings`
+    pub fn operator(&self, max_servings: u32) -> DynamicMutator {
+        match *self {
+            MutationConfig::Inversion { rate } => DynamicMutator::Inversion(InversionMutator { mutation_rate: rate }),
+            MutationConfig::Creep { rate, max_step, sigma } => {
+                DynamicMutator::Creep(CreepMutator { mutation_rate: rate, max_step, sigma, max_value: max_servings })
+            }
+        }
+    }
+}
+
+/// How the children make it into the next generation, `replace_ratio` of the population is replaced
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum ReinsertionConfig {
+    /// The fittest of the children replace the least fit individuals
+    Elitist {
+        #[serde(default = "default_replace_ratio")]
+        replace_ratio: f64,
+        /// Children replace individuals even when they are less fit
+        #[serde(default)]
+        offspring_has_precedence: bool,
+    },
+    /// Random children replace random individuals
+    Uniform {
+        #[serde(default = "default_replace_ratio")]
+        replace_ratio: f64,
+    },
+}
+
+fn default_replace_ratio() -> f64 {
+    0.85
+}
+
+impl ReinsertionConfig {
+    pub fn elitist() -> Self {
+        ReinsertionConfig::Elitist { replace_ratio: default_replace_ratio(), offspring_has_precedence: false }
+    }
+
+    pub fn uniform() -> Self {
+        ReinsertionConfig::Uniform { replace_ratio: default_replace_ratio() }
+    }
+
+    /// The elitist reinserter ranks the children with `fitness_function`
+    pub fn operator<G, F, E>(&self, fitness_function: E) -> DynamicReinserter<G, F, E>
+        where
+            G: Genotype,
+            F: Fitness,
+            E: FitnessFunction<G, F>,
+    {
+        match *self {
+            ReinsertionConfig::Elitist { replace_ratio, offspring_has_precedence } => {
+                DynamicReinserter::Elitist(ElitistReinserter::new(fitness_function, offspring_has_precedence, replace_ratio))
+            }
+            ReinsertionConfig::Uniform { replace_ratio } => DynamicReinserter::Uniform(UniformReinserter::new(replace_ratio)),
+        }
+    }
+
+    fn replace_ratio(&self) -> f64 {
+        match *self {
+            ReinsertionConfig::Elitist { replace_ratio, .. } | ReinsertionConfig::Uniform { replace_ratio } => replace_ratio,
+        }
+    }
+}
+
+/// genevo's builder rejects smaller populations once the simulation starts, its
+/// `DEFAULT_MIN_POPULATION_SIZE` is private
+const MIN_POPULATION: usize = 6;
+
+/// Everything that tunes the genetic algorithm, loadable from a TOML file. Missing
+/// keys take their default value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GaConfig {
+    /// Number of individuals in the population
+    pub population: usize,
+    /// Number of generations
+    pub generations: usize,
+    pub selector: SelectorConfig,
+    pub crossover: CrossoverConfig,
+    pub mutation: MutationConfig,
+    pub reinsertion: ReinsertionConfig,
+    /// Share of the children repaired towards a feasible diet after mutating, 0 leaves
+    /// the constraints to the penalties of the objective
+    pub repair_rate: f64,
+    /// Fewest servings of a dish a gene of the initial population holds
+    pub min_servings: u32,
+    /// Most servings of a dish a gene of the initial population holds, the problem's cap when unset
+    pub max_servings: Option<u32>,
+    /// Seed for a reproducible run, a random one is used otherwise
+    pub seed: Option<u64>,
+}
+
+impl Default for GaConfig {
+    fn default() -> Self {
+        Self {
+            population: 15,
+            generations: 1000,
+            selector: SelectorConfig::tournament(),
+            crossover: CrossoverConfig::SinglePoint,
+            mutation: MutationConfig::inversion(),
+            reinsertion: ReinsertionConfig::elitist(),
+            repair_rate: 0.0,
+            min_servings: 0,
+            max_servings: None,
+            seed: None,
+        }
+    }
+}
+
+impl GaConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+        let config: GaConfig = toml::from_str(&contents).map_err(|error| ConfigError::Parse(error.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check(
+            self.population >= MIN_POPULATION,
+            &format!("population must be at least {} (genevo's minimum population size)", MIN_POPULATION),
+        )?;
+        check(self.generations > 0, "generations must be at least 1")?;
+        check(self.selector.selection_ratio() > 0.0 && self.selector.selection_ratio() <= 1.0, "selection_ratio must be between 0 and 1")?;
+        check(self.selector.individuals_per_parent() >= 2, "individuals_per_parent must be at least 2")?;
+        if let SelectorConfig::Tournament { tournament_size, probability, .. } = self.selector {
+            check(tournament_size > 0, "tournament_size must be at least 1")?;
+            check(probability > 0.0 && probability <= 1.0, "tournament probability must be between 0 and 1")?;
+        }
+        match self.crossover {
+            CrossoverConfig::MultiPoint { cut_points } => check(cut_points > 0, "cut_points must be at least 1")?,
+            CrossoverConfig::Blend { alpha } => check(alpha >= 0.0 && alpha.is_finite(), "blend alpha must not be negative")?,
+            CrossoverConfig::SinglePoint | CrossoverConfig::Uniform | CrossoverConfig::Arithmetic | CrossoverConfig::Nutrient => {}
+        }
+        match self.mutation {
+            MutationConfig::Inversion { rate } => check((0.0..=1.0).contains(&rate), "mutation rate must be between 0 and 1")?,
+            MutationConfig::Creep { rate, max_step, sigma } => {
+                check((0.0..=1.0).contains(&rate), "mutation rate must be between 0 and 1")?;
+                check(max_step > 0, "max_step must be at least 1")?;
+                check(sigma.is_none_or(|sigma| sigma > 0.0 && sigma.is_finite()), "sigma must be positive")?;
+            }
+        }
+        check((0.0..=1.0).contains(&self.reinsertion.replace_ratio()), "replace_ratio must be between 0 and 1")?;
+        check((0.0..=1.0).contains(&self.repair_rate), "repair_rate must be between 0 and 1")?;
+        check(self.max_servings != Some(0), "max_servings must be at least 1")?;
+        check(self.max_servings.is_none_or(|max_servings| self.min_servings <= max_servings), "min_servings must not exceed max_servings")
+    }
+
+    /// genevo's cut points assert on short genomes, so the operators are checked
+    /// against the number of genes (one per dish) before the simulation starts
+    pub fn validate_genes(&self, genes: usize) -> Result<(), ConfigError> {
+        let crossover_genes = match self.crossover {
+            CrossoverConfig::SinglePoint => Some(("single-point crossover", 2)),
+            CrossoverConfig::MultiPoint { cut_points } => Some(("multi-point crossover", 2 * cut_points)),
+            CrossoverConfig::Uniform | CrossoverConfig::Blend { .. } | CrossoverConfig::Arithmetic | CrossoverConfig::Nutrient => None,
+        };
+        let mutation_genes = match self.mutation {
+            MutationConfig::Inversion { .. } => Some(("inversion mutation", 4)),
+            MutationConfig::Creep { .. } => None,
+        };
+        for (operator, needed) in crossover_genes.into_iter().chain(mutation_genes) {
+            check(genes >= needed, &format!("{} needs at least {} dishes, the catalog has {}", operator, needed, genes))?;
+        }
+        Ok(())
+    }
+}
@@ -1,120 +1,58 @@
-use genevo::operator::prelude::*;
 use genevo::population::ValueEncodedGenomeBuilder;
 use genevo::prelude::*;
-use crate::dish::{Dish, get_dishes};
-use crate::dynamic_selector::{DynamicSelector, GenevoSelector};
-use crate::inversion_mutator::InversionMutator;
-
-/// The phenotype
-#[derive(Debug)]
-struct Diet {
-    dishes: Vec<(Dish, u32)>,
-    total_calories: u64,
-    total_carbs: u64,
-    total_fats: u64,
-    total_proteins: u64,
-    price: u64,
-}
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::error::Error;
+use std::time::Instant;
+use crate::aco_config::ConfigError;
+use crate::dish::Dish;
+use crate::ga_config::GaConfig;
+use crate::problem::{DietProblem, DietTotals};
+use crate::repair::{Repair, RepairingMutator};
+use crate::solver::{DishCount, RunResult, Solution, Solver, SolverError};
 
 /// The genotype
 type Selection = Vec<u32>;
 
 /// How do the genes of the genotype show up in the phenotype
 trait AsPhenotype {
-    fn as_diet(&self, all_dishes: &[Dish]) -> Diet;
+    fn as_diet(&self) -> Vec<DishCount>;
 }
 
 impl AsPhenotype for Selection {
-    fn as_diet(&self, all_dishes: &[Dish]) -> Diet {
-        let dishes: Vec<(Dish, u32)> = self
-            .iter()
+    fn as_diet(&self) -> Vec<DishCount> {
+        self.iter()
             .enumerate()
-            .filter_map(|(index, dish_count)| {
-                if *dish_count > 0 {
-                    Some((all_dishes[index].clone(), *dish_count))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        let total_calories = dishes.iter().map(|(dish, count)| (dish.calories * count) as u64).sum::<u64>();
-        let price = dishes.iter().map(|(dish, count)| (dish.price * count) as u64).sum::<u64>();
-        let total_carbs = dishes.iter().map(|(dish, count)| (dish.carbs * count) as u64).sum::<u64>();
-        let total_fats = dishes.iter().map(|(dish, count)| (dish.fats * count) as u64).sum::<u64>();
-        let total_proteins = dishes.iter().map(|(dish, count)| (dish.proteins * count) as u64).sum::<u64>();
-
-        Diet {
-            dishes,
-            total_calories,
-            price,
-            total_carbs,
-            total_fats,
-            total_proteins,
-        }
+            .filter(|(_, dish_count)| **dish_count > 0)
+            .map(|(dish, count)| DishCount { dish, count: *count as usize })
+            .collect()
     }
 }
 
+/// genevo fitness has to be an integer, so the shared objective is scaled before rounding
+const FITNESS_SCALE: f64 = 1000.0;
+
 /// The problem definition
 #[derive(Debug, Clone)]
 struct Problem<'a> {
-    all_dishes: &'a Vec<Dish>,
-    target_calories: u64,
-    target_carbs: u64,
-    target_fats: u64,
-    target_proteins: u64,
+    all_dishes: &'a [Dish],
+    diet_problem: &'a DietProblem,
 }
 
 impl<'a> Problem<'a> {
-    pub fn new(target_calories: u64, target_carbs: u64, target_fats: u64, target_proteins: u64, all_dishes: &'a Vec<Dish>) -> Self {
+    pub fn new(diet_problem: &'a DietProblem, all_dishes: &'a [Dish]) -> Self {
         Self {
             all_dishes,
-            target_calories,
-            target_carbs,
-            target_fats,
-            target_proteins,
+            diet_problem,
         }
     }
 }
 
 /// The fitness function for `Selection`
-impl<'a, 'b> FitnessFunction<Selection, i64> for &'b Problem<'a> {
+impl FitnessFunction<Selection, i64> for &Problem<'_> {
     fn fitness_of(&self, selection: &Selection) -> i64 {
-        let (total_calories, total_carbs, total_fats, total_proteins, total_price) = selection
-            .iter()
-            .enumerate()
-            .filter_map(|(index, dish_count)| {
-                if *dish_count > 0 {
-                    let dish = &self.all_dishes[index];
-                    Some(((u64::from(dish.calories * dish_count)),
-                          (u64::from(dish.carbs * dish_count)),
-                          (u64::from(dish.fats * dish_count)),
-                          (u64::from(dish.proteins * dish_count)),
-                          i64::from(dish.price * dish_count)))
-                } else {
-                    None
-                }
-            })
-            .fold((0, 0, 0, 0, 0), |(total_calories, total_carbs, total_fats, total_proteins, total_price), (calories, carbs, fats, proteins, price)| {
-                (total_calories + calories, total_carbs + carbs, total_fats + fats, total_proteins + proteins, total_price + price)
-            });
-
-        let nil = -99999;
-        let mut sum: i64 = 9999999;
-        if total_calories.abs_diff(self.target_calories) > 200 {
-            sum += nil;
-        }
-        if total_carbs.abs_diff(self.target_carbs) > 25 {
-            sum += nil
-        }
-        if total_fats.abs_diff(self.target_fats) > 10 {
-            sum += nil
-        }
-        if total_proteins.abs_diff(self.target_proteins) > 20 {
-            sum += nil
-        }
-
-        sum - total_price.pow(2) + (total_calories + total_carbs + total_fats + total_proteins) as i64
+        let totals = DietTotals::of(self.all_dishes, selection.iter().copied().enumerate());
+        (self.diet_problem.objective(&totals) * FITNESS_SCALE).round() as i64
     }
 
     fn average(&self, values: &[i64]) -> i64 {
@@ -122,127 +60,155 @@ impl<'a, 'b> FitnessFunction<Selection, i64> for &'b Problem<'a> {
     }
 
     fn highest_possible_fitness(&self) -> i64 {
-        0
+        i64::MAX
     }
 
-    fn lowest_possible_fitness(&self) -> i64 { -9999999 }
+    fn lowest_possible_fitness(&self) -> i64 { i64::MIN }
 }
 
-fn run(selector: DynamicSelector, all_dishes: &Vec<Dish>, generation_count: u64) -> (i64, usize) {
-    let problem = Problem::new(2250, 275, 50, 120, all_dishes);
-    let max_dish_count = 10;
-
+/// Evolves `config.population` diets for `generation_count` generations, with the
+/// gene bounds already resolved against the problem's cap
+fn run(config: &GaConfig, max_servings: u32, all_dishes: &[Dish], diet_problem: &DietProblem, generation_count: u64, seed: u64) -> Result<RunResult, SolverError> {
+    let started_at = Instant::now();
+    // genevo wants 32 byte seeds for the initial population and the simulation
+    let mut seed_rng = StdRng::seed_from_u64(seed);
+    let population_seed: Seed = seed_rng.gen();
+    let simulation_seed: Seed = seed_rng.gen();
+    let problem = Problem::new(diet_problem, all_dishes);
+
+    // the upper bound of the genome builder is exclusive
     let initial_population: Population<Selection> = build_population()
         .with_genome_builder(ValueEncodedGenomeBuilder::new(
-            problem.all_dishes.len(), 0, max_dish_count,
+            problem.all_dishes.len(), config.min_servings, max_servings + 1,
         ))
-        .of_size(15)
-        .uniform_at_random();
+        .of_size(config.population)
+        .using_seed(population_seed);
 
     let mut diet_sim = simulate(
         genetic_algorithm()
             .with_evaluation(&problem)
-            .with_selection(selector)
-            .with_crossover(SinglePointCrossBreeder::new())
-            .with_mutation(InversionMutator { mutation_rate: 0.1 })
-            .with_reinsertion(ElitistReinserter::new(&problem, false, 0.85))
-            // .with_reinsertion(UniformReinserter::new(0.85))
+            .with_selection(config.selector.operator())
+            .with_crossover(config.crossover.operator(all_dishes, diet_problem, max_servings))
+            .with_mutation(RepairingMutator {
+                mutator: config.mutation.operator(max_servings),
+                repair: (config.repair_rate > 0.0).then_some(Repair {
+                    dishes: all_dishes,
+                    problem: diet_problem,
+                    rate: config.repair_rate,
+                    max_value: max_servings,
+                }),
+            })
+            .with_reinsertion(config.reinsertion.operator(&problem))
             .with_initial_population(initial_population)
             .build(),
     )
         .until(GenerationLimit::new(generation_count))
-        .build();
-
-    let mut fitness: i64 = 0;
-    let mut unique_dishes: usize = 0;
-
-    'sim: loop {
-        let result = diet_sim.step();
-
-        match result {
-            Ok(SimResult::Intermediate(step)) => {
-                // println!("{:?}", &step.result.evaluated_population);
-                // let evaluated_population = step.result.evaluated_population;
-                // let best_solution = step.result.best_solution;
-                // println!(
-                //     "step: generation: {}, average_fitness: {}, \
-                //      best fitness: {}, duration: {}, processing_time: {}",
-                //     step.iteration,
-                //     evaluated_population.average_fitness(),
-                //     best_solution.solution.fitness,
-                //     step.duration.fmt(),
-                //     step.processing_time.fmt(),
-                // );
-                // let diet = best_solution
-                //     .solution
-                //     .genome
-                //     .as_diet(&problem.all_dishes);
-                // println!(
-                //     "      Diet: number of unique dishes: {}, total calories: {}, total price: {}",
-                //     diet.dishes.len(),
-                //     diet.total_calories,
-                //     diet.price,
-                // );
-            }
-            Ok(SimResult::Final(step, processing_time, duration, stop_reason)) => {
-                let best_solution = step.result.best_solution;
-                // println!("{}", stop_reason);
-                // println!(
-                //     "Final result after {}: generation: {}, \
-                //      best solution with fitness {} found in generation {}, processing_time: {}",
-                //     duration.fmt(),
-                //     step.iteration,
-                //     best_solution.solution.fitness,
-                //     best_solution.generation,
-                //     processing_time.fmt(),
-                // );
-                // let diet = best_solution
-                //     .solution
-                //     .genome
-                //     .as_diet(problem.all_dishes);
-                // println!(
-                //     "      Diet: number of unique dishes: {}, total calories: {}, total price: {}",
-                //     diet.dishes.len(),
-                //     diet.total_calories,
-                //     diet.price,
-                // );
-                // println!("{:?}", diet.dishes.into_iter().map(|(dish, count)| (dish.name, count)).collect::<Vec<(String, u32)>>());
-                fitness = best_solution.solution.fitness;
-                unique_dishes = best_solution.solution.genome.as_diet(problem.all_dishes).dishes.len();
-                // println!("fitness: {}, unique dishes: {}", fitness, unique_dishes);
-                break 'sim;
-            }
-            Err(error) => {
-                println!("{}", error);
-                break 'sim;
-            }
+        .build_with_seed(simulation_seed);
+
+    let mut best: (i64, Selection) = (i64::MIN, vec![]);
+    let mut history = Vec::with_capacity(generation_count as usize);
+    let mut evaluations: u64 = 0;
+
+    loop {
+        let (step, finished) = match diet_sim.step() {
+            Ok(SimResult::Intermediate(step)) => (step, false),
+            Ok(SimResult::Final(step, _processing_time, _duration, _stop_reason)) => (step, true),
+            Err(error) => return Err(SolverError::Algorithm(error.to_string())),
+        };
+
+        let generation_best = step.result.best_solution.solution;
+        if best.1.is_empty() || generation_best.fitness > best.0 {
+            best = (generation_best.fitness, generation_best.genome);
+        }
+        evaluations += step.result.evaluated_population.fitness_values().len() as u64;
+        history.push(best.0 as f64 / FITNESS_SCALE);
+
+        if finished {
+            break;
         }
     }
 
-    (fitness, unique_dishes)
+    Ok(RunResult {
+        solver: "genetic algorithm".into(),
+        best: Solution::new(best.1.as_diet(), all_dishes, diet_problem),
+        history,
+        found_in: None,
+        iterations: vec![],
+        diagnostics: vec![],
+        baseline: None,
+        seed,
+        evaluations,
+        elapsed: started_at.elapsed(),
+    })
 }
 
-pub fn gen_algorithm()
-{
-    let dishes = get_dishes();
+/// The genevo based genetic algorithm as a `Solver`
+pub struct GaSolver {
+    dishes: Vec<Dish>,
+    problem: DietProblem,
+    config: GaConfig,
+    /// Most servings of a dish a gene starts with
+    max_servings: u32,
+    seed: u64,
+}
 
-    // let selector = DynamicSelector::new(GenevoSelector::Maximize(MaximizeSelector::new(0.85, 12)));
-    // let selector = DynamicSelector::new(GenevoSelector::Roulette(RouletteWheelSelector::new(0.85, 12)));
-    let selector = DynamicSelector::new(GenevoSelector::Tournament(TournamentSelector::new(0.85, 12, 3, 1.0, false)));
+impl GaSolver {
+    /// Sets up the algorithm for `problem`, failing when the config is invalid
+    pub fn new(dishes: Vec<Dish>, problem: DietProblem, config: GaConfig) -> Result<Self, ConfigError> {
+        problem.validate()?;
+        config.validate()?;
+        if dishes.is_empty() {
+            return Err(ConfigError::Invalid("the genetic algorithm needs at least one dish".into()));
+        }
+        config.validate_genes(dishes.len())?;
+        let max_servings = config.max_servings.unwrap_or(problem.max_servings);
+        if max_servings > problem.max_servings {
+            return Err(ConfigError::Invalid(format!(
+                "max_servings {} is above the cap of the problem ({})", max_servings, problem.max_servings,
+            )));
+        }
+        if config.min_servings > max_servings {
+            return Err(ConfigError::Invalid("min_servings must not exceed max_servings".into()));
+        }
+        let seed = config.seed.unwrap_or_else(rand::random);
+        Ok(Self { dishes, problem, config, max_servings, seed })
+    }
+}
 
-    let gen_fitness_dishes: Vec<(u64, i64, usize)> = [1, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 200, 300, 400, 500, 600, 700, 800, 900, 1000, 10000].into_iter().map(|generation_count| {
-        let i = 5;
-        let (fitness_sum, dish_count_sum) = (0..i)
-            .map(|_| {
-                run(selector.clone(), &dishes, generation_count)
-            })
-            .fold((0, 0), |(fitness_sum, dish_count_sum), (fitness, dish_count)| (fitness_sum + fitness, dish_count_sum + dish_count));
+impl Solver for GaSolver {
+    fn name(&self) -> String {
+        "genetic algorithm".into()
+    }
 
-        (generation_count, fitness_sum / i, dish_count_sum / i as usize)
-    }).collect();
+    fn solve(&mut self, iterations: usize) -> Result<RunResult, SolverError> {
+        run(&self.config, self.max_servings, &self.dishes, &self.problem, iterations as u64, self.seed)
+    }
+}
+
+/// Averages `repeats` runs of `config` for every generation count and prints them as table rows
+pub fn gen_algorithm(dishes: &[Dish], problem: &DietProblem, config: GaConfig, generation_counts: &[usize], repeats: usize) -> Result<(), Box<dyn Error>>
+{
+    // fail before the sweep starts
+    GaSolver::new(dishes.to_vec(), problem.clone(), config.clone())?;
+    if repeats == 0 {
+        return Err(ConfigError::Invalid("repeats must be at least 1".into()).into());
+    }
+    let mut gen_fitness_dishes: Vec<(usize, f64, usize)> = Vec::with_capacity(generation_counts.len());
+    for &generation_count in generation_counts {
+        let (mut fitness_sum, mut dish_count_sum) = (0.0, 0);
+        for repeat in 0..repeats {
+            // every repeat needs a different run, but the whole sweep stays reproducible
+            let seed = config.seed.map(|seed| seed.wrapping_add(repeat as u64));
+            let mut solver = GaSolver::new(dishes.to_vec(), problem.clone(), GaConfig { seed, ..config.clone() })?;
+            let result = solver.solve(generation_count)?;
+            fitness_sum += result.best.objective;
+            dish_count_sum += result.best.diet.len();
+        }
+        gen_fitness_dishes.push((generation_count, fitness_sum / repeats as f64, dish_count_sum / repeats));
+    }
 
     for (generation, average_fitness, average_dishes) in gen_fitness_dishes {
-        // println!("|{}|{}|{}|", generation, average_fitness, average_dishes)
-        println!("{}", average_fitness - 9000000)
+        println!("|{}|{}|{}|", generation, average_fitness, average_dishes)
     }
-}
\ No newline at end of file
+    Ok(())
+}
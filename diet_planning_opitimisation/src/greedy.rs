@@ -0,0 +1,78 @@
+use std::time::Instant;
+use crate::dish::Dish;
+use crate::problem::{DietProblem, DietTotals, InvalidProblem};
+use crate::solver::{DishCount, RunResult, Solution, Solver, SolverError};
+
+/// Builds a diet one serving at a time, always taking the serving that closes the most
+/// of the gaps still left below the targets per unit of price. Stops once the targets
+/// are met or no serving that fits the budget and the nutrient bounds closes a gap.
+pub fn greedy_diet(dishes: &[Dish], problem: &DietProblem, max_servings: usize) -> Vec<DishCount> {
+    let mut diet: Vec<DishCount> = Vec::new();
+    let mut counts = vec![0; dishes.len()];
+    let mut totals = DietTotals::default();
+    while !problem.targets_met(&totals) {
+        let Some(dish) = best_gap_filler(dishes, problem, &totals, |dish| counts[dish] < max_servings) else {
+            break;
+        };
+        match diet.iter_mut().find(|dish_count| dish_count.dish == dish) {
+            Some(dish_count) => dish_count.count += 1,
+            None => diet.push(DishCount { dish, count: 1 }),
+        }
+        counts[dish] += 1;
+        totals.add(&dishes[dish], 1);
+    }
+    diet
+}
+
+/// The dish whose next serving closes the most of the gaps below the targets per unit of
+/// price, among the `open` ones that fit the budget and the nutrient bounds
+pub fn best_gap_filler<F>(dishes: &[Dish], problem: &DietProblem, totals: &DietTotals, open: F) -> Option<usize>
+    where
+        F: Fn(usize) -> bool,
+{
+    (0..dishes.len())
+        .filter(|&dish| open(dish) && problem.fits(totals, &dishes[dish], 1))
+        .map(|dish| (dish, problem.gap_closed(totals, &dishes[dish], 1) / dishes[dish].price as f64))
+        .filter(|&(_, score)| score > 0.0)
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .map(|(dish, _)| dish)
+}
+
+/// The greedy construction as a `Solver`, the baseline the other solvers should beat
+pub struct GreedySolver {
+    dishes: Vec<Dish>,
+    problem: DietProblem,
+}
+
+impl GreedySolver {
+    /// Sets up the construction for `problem`, failing when it can't be solved
+    pub fn new(dishes: Vec<Dish>, problem: DietProblem) -> Result<Self, InvalidProblem> {
+        problem.validate()?;
+        Ok(Self { dishes, problem })
+    }
+}
+
+impl Solver for GreedySolver {
+    fn name(&self) -> String {
+        "greedy".into()
+    }
+
+    /// The construction is deterministic, `iterations` is ignored
+    fn solve(&mut self, _iterations: usize) -> Result<RunResult, SolverError> {
+        let started_at = Instant::now();
+        let diet = greedy_diet(&self.dishes, &self.problem, self.problem.max_servings as usize);
+        let best = Solution::new(diet, &self.dishes, &self.problem);
+        Ok(RunResult {
+            solver: self.name(),
+            history: vec![best.objective],
+            found_in: None,
+            iterations: vec![],
+            diagnostics: vec![],
+            baseline: None,
+            seed: 0,
+            evaluations: best.diet.iter().map(|dish_count| dish_count.count as u64).sum(),
+            elapsed: started_at.elapsed(),
+            best,
+        })
+    }
+}
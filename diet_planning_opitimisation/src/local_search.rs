@@ -0,0 +1,78 @@
+use crate::dish::Dish;
+use crate::problem::{DietProblem, DietTotals};
+use crate::solver::DishCount;
+
+/// A single change to a diet, positions index into the diet
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Change {
+    /// One more serving of a dish, added to the diet when it isn't in it yet
+    Add(usize),
+    /// One serving less of the dish at a position that has more than one
+    Remove(usize),
+    /// The dish at a position replaced by another one with the same servings
+    Swap(usize, usize),
+    /// The dish at a position left out of the diet
+    Drop(usize),
+}
+
+/// Hill climbs from `diet` through ±1 serving changes, swaps and drops, taking the
+/// first change that improves the objective until none does. Returns the totals of
+/// the improved diet.
+pub fn local_search(diet: &mut Vec<DishCount>, dishes: &[Dish], problem: &DietProblem, max_servings: usize) -> DietTotals {
+    let mut totals = DietTotals::of(dishes, diet.iter().map(|dish_count| (dish_count.dish, dish_count.count as u32)));
+    while let Some((change, improved)) = first_improvement(diet, &totals, dishes, problem, max_servings) {
+        apply(diet, change);
+        totals = improved;
+    }
+    totals
+}
+
+fn first_improvement(diet: &[DishCount], totals: &DietTotals, dishes: &[Dish], problem: &DietProblem, max_servings: usize) -> Option<(Change, DietTotals)> {
+    let objective = problem.objective(totals);
+    let mut positions = vec![None; dishes.len()];
+    for (position, dish_count) in diet.iter().enumerate() {
+        positions[dish_count.dish] = Some(position);
+    }
+
+    let drops = (0..diet.len()).map(Change::Drop);
+    let removes = (0..diet.len()).filter(|&position| diet[position].count > 1).map(Change::Remove);
+    let adds = (0..dishes.len())
+        .filter(|&dish| positions[dish].is_none_or(|position| diet[position].count < max_servings))
+        .map(Change::Add);
+    let swaps = (0..diet.len()).flat_map(|position| {
+        (0..dishes.len()).filter(|&dish| positions[dish].is_none()).map(move |dish| Change::Swap(position, dish))
+    });
+
+    drops.chain(removes).chain(adds).chain(swaps)
+        .map(|change| (change, changed_totals(diet, totals, dishes, change)))
+        .find(|(_, candidate)| problem.objective(candidate) > objective)
+}
+
+fn changed_totals(diet: &[DishCount], totals: &DietTotals, dishes: &[Dish], change: Change) -> DietTotals {
+    let mut totals = *totals;
+    match change {
+        Change::Add(dish) => totals.add(&dishes[dish], 1),
+        Change::Remove(position) => totals.remove(&dishes[diet[position].dish], 1),
+        Change::Swap(position, dish) => {
+            let dish_count = diet[position];
+            totals.remove(&dishes[dish_count.dish], dish_count.count as u32);
+            totals.add(&dishes[dish], dish_count.count as u32);
+        }
+        Change::Drop(position) => totals.remove(&dishes[diet[position].dish], diet[position].count as u32),
+    }
+    totals
+}
+
+fn apply(diet: &mut Vec<DishCount>, change: Change) {
+    match change {
+        Change::Add(dish) => match diet.iter_mut().find(|dish_count| dish_count.dish == dish) {
+            Some(dish_count) => dish_count.count += 1,
+            None => diet.push(DishCount { dish, count: 1 }),
+        },
+        Change::Remove(position) => diet[position].count -= 1,
+        Change::Swap(position, dish) => diet[position].dish = dish,
+        Change::Drop(position) => {
+            diet.remove(position);
+        }
+    }
+}
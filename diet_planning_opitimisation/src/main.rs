@@ -1,14 +1,136 @@
 mod dynamic_selector;
 mod inversion_mutator;
+mod creep_mutator;
+mod blend_crossover;
+mod nutrient_crossover;
+mod repair;
 mod dish;
+mod catalog;
+mod problem;
+mod solver;
+mod greedy;
+mod aco_config;
+mod ga_config;
+mod dynamic_operators;
 mod genetic_algorithm;
+mod pheromone;
+mod sampling;
+mod local_search;
 mod ant_colony;
+mod multi_colony;
+mod cli;
+mod plot;
+mod report;
 
-use rayon::ThreadPoolBuilder;
-use crate::ant_colony::{ant_colony_algorithm};
+use std::error::Error;
+use std::fs;
+use std::process;
+use clap::Parser;
+use crate::aco_config::{AcoConfig, ConfigError};
+use crate::ga_config::GaConfig;
+use crate::ant_colony::{ant_colony_algorithm, AntColony};
+use crate::cli::{Algorithm, Cli, Command};
+use crate::genetic_algorithm::{gen_algorithm, GaSolver};
+use crate::greedy::GreedySolver;
+use crate::multi_colony::MultiColony;
+use crate::plot::plot_convergence;
+use crate::dish::synthetic_dishes;
+use crate::report::{emit, load_result, print_benchmark, save_pheromone};
+use crate::solver::Solver;
+
+/// Iterations of `diet bench`, enough to measure the throughput without waiting for convergence
+const BENCH_ITERATIONS: usize = 20;
 
 fn main() {
-    // gen_algorithm();
+    if let Err(error) = run(Cli::parse()) {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
 
-    ant_colony_algorithm();
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        Command::Ga(args) => {
+            let (dishes, problem) = args.problem.load()?;
+            let config = args.evolution.config()?;
+            let generations = config.generations;
+            let mut solver = GaSolver::new(dishes.clone(), problem.clone(), config)?;
+            let result = solver.solve(generations)?;
+            emit(&result, &dishes, &problem, &args.output)?;
+        }
+        Command::Greedy(args) => {
+            let (dishes, problem) = args.problem.load()?;
+            let result = GreedySolver::new(dishes.clone(), problem.clone())?.solve(1)?;
+            emit(&result, &dishes, &problem, &args.output)?;
+        }
+        Command::Aco(args) => {
+            let (dishes, problem) = args.problem.load()?;
+            let config = args.colony.config()?;
+            if config.snapshot_every.is_some() && args.output.output_dir.is_none() {
+                return Err(ConfigError::Invalid("pheromone snapshots need an --output-dir to be saved into".into()).into());
+            }
+            let iterations = config.iterations;
+            let mut solver = AntColony::new(dishes.clone(), problem.clone(), config)?;
+            let result = solver.solve(iterations)?;
+            emit(&result, &dishes, &problem, &args.output)?;
+            if let Some(output_dir) = &args.output.output_dir {
+                if !solver.snapshots().is_empty() {
+                    save_pheromone(solver.trails(), solver.snapshots(), &dishes, output_dir)?;
+                }
+            }
+        }
+        Command::Multi(args) => {
+            let (dishes, problem) = args.problem.load()?;
+            let config = args.colony.config()?;
+            let iterations = config.iterations;
+            let mut solver = MultiColony::new(dishes.clone(), problem.clone(), config, &args.colonies()?, args.migration_interval, args.migration())?;
+            let result = solver.solve(iterations)?;
+            emit(&result, &dishes, &problem, &args.output)?;
+        }
+        Command::Sweep(args) => {
+            let (dishes, problem) = args.problem.load()?;
+            fs::create_dir_all(&args.output_dir)?;
+            match args.algorithm {
+                Algorithm::Aco => {
+                    let mut config = match &args.config {
+                        Some(path) => AcoConfig::load(path)?,
+                        None => AcoConfig::default(),
+                    };
+                    config.seed = args.seed.or(config.seed);
+                    ant_colony_algorithm(dishes, problem, config, &args.ants, &args.iterations, &args.output_dir)?
+                }
+                Algorithm::Ga => {
+                    let mut config = match &args.config {
+                        Some(path) => GaConfig::load(path)?,
+                        None => GaConfig::default(),
+                    };
+                    config.population = args.population.unwrap_or(config.population);
+                    config.seed = args.seed.or(config.seed);
+                    gen_algorithm(&dishes, &problem, config, &args.iterations, args.repeats)?
+                }
+            }
+        }
+        Command::Plot(args) => {
+            let results = args.results
+                .iter()
+                .map(|path| load_result(path))
+                .collect::<Result<Vec<_>, _>>()?;
+            fs::create_dir_all(&args.output_dir)?;
+            plot_convergence(&results, &args.output_dir.join("convergence.png"))?;
+        }
+        Command::Bench(args) => {
+            let (mut dishes, problem) = args.problem.load()?;
+            let mut config = args.colony.config()?;
+            config.iterations = args.colony.iterations.unwrap_or(BENCH_ITERATIONS);
+            config.seed = Some(config.seed.unwrap_or(0));
+            if let Some(count) = args.synthetic_dishes {
+                dishes = synthetic_dishes(count, config.seed.unwrap());
+            }
+            let iterations = config.iterations;
+            let mut solver = AntColony::new(dishes.clone(), problem, config)?;
+            let result = solver.solve(iterations)?;
+            print_benchmark(&result, &dishes);
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,135 @@
+use std::time::Instant;
+use rayon::prelude::*;
+use crate::aco_config::{AcoConfig, ConfigError};
+use crate::ant_colony::AntColony;
+use crate::dish::Dish;
+use crate::problem::DietProblem;
+use crate::solver::{RunResult, Solution, Solver, SolverError};
+
+/// How the colonies share what they learned, every colony receives from its neighbour in a ring
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Migration {
+    /// The neighbour's best path is deposited on the trails and may become the colony's best
+    BestPath,
+    /// The trails move the share `weight` of the way towards the neighbour's
+    BlendTrails { weight: f64 },
+}
+
+/// The parameters a colony of a multi-colony run doesn't share with the others
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColonyParams {
+    pub alpha: f64,
+    pub beta: f64,
+    pub evaporation: f64,
+}
+
+/// Several differently tuned colonies searching in parallel and exchanging their
+/// findings every `migration_interval` iterations
+pub struct MultiColony {
+    dishes: Vec<Dish>,
+    problem: DietProblem,
+    colonies: Vec<AntColony>,
+    migration_interval: usize,
+    migration: Migration,
+    seed: u64,
+}
+
+impl MultiColony {
+    /// One colony per entry of `params`, each being `base` with its own alpha, beta and evaporation
+    pub(crate) fn new(dishes: Vec<Dish>, problem: DietProblem, base: AcoConfig, params: &[ColonyParams], migration_interval: usize, migration: Migration) -> Result<Self, ConfigError> {
+        if params.is_empty() {
+            return Err(ConfigError::Invalid("a multi-colony run needs at least one colony".into()));
+        }
+        if migration_interval == 0 {
+            return Err(ConfigError::Invalid("migration_interval must be at least 1".into()));
+        }
+        if let Migration::BlendTrails { weight } = migration {
+            if !(0.0..=1.0).contains(&weight) {
+                return Err(ConfigError::Invalid("blend weight must be between 0 and 1".into()));
+            }
+        }
+
+        let seed = base.seed.unwrap_or_else(rand::random);
+        let colonies = params.iter()
+            .enumerate()
+            .map(|(index, params)| {
+                let config = AcoConfig {
+                    alpha: params.alpha,
+                    beta: params.beta,
+                    evaporation: params.evaporation,
+                    seed: Some(seed.wrapping_add(index as u64)),
+                    ..base.clone()
+                };
+                AntColony::new(dishes.clone(), problem.clone(), config)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { dishes, problem, colonies, migration_interval, migration, seed })
+    }
+
+    fn migrate(&mut self) {
+        let count = self.colonies.len();
+        match self.migration {
+            Migration::BestPath => {
+                let migrants: Vec<_> = self.colonies.iter()
+                    .map(|colony| colony.best().map(|(path, _)| path.to_vec()))
+                    .collect();
+                for (index, colony) in self.colonies.iter_mut().enumerate() {
+                    if let Some(path) = &migrants[(index + count - 1) % count] {
+                        colony.immigrate(path);
+                    }
+                }
+            }
+            Migration::BlendTrails { weight } => {
+                let trails: Vec<_> = self.colonies.iter().map(|colony| colony.trails().clone()).collect();
+                for (index, colony) in self.colonies.iter_mut().enumerate() {
+                    colony.blend_trails(&trails[(index + count - 1) % count], weight);
+                }
+            }
+        }
+    }
+}
+
+impl Solver for MultiColony {
+    fn name(&self) -> String {
+        "multi colony".into()
+    }
+
+    fn solve(&mut self, iterations: usize) -> Result<RunResult, SolverError> {
+        let started_at = Instant::now();
+        let mut history = Vec::new();
+        while history.len() < iterations {
+            let steps = self.migration_interval.min(iterations - history.len());
+            let histories: Vec<Vec<f64>> = self.colonies.par_iter_mut()
+                .map(|colony| colony.run(steps).map(|run| run.history))
+                .collect::<Result<_, _>>()?;
+            // colonies stopping early on stagnation keep their last best
+            for step in 0..steps {
+                let best = histories.iter()
+                    .filter_map(|colony_history| colony_history.get(step).or(colony_history.last()))
+                    .cloned()
+                    .fold(f64::MIN, f64::max);
+                history.push(history.last().map_or(best, |&previous: &f64| previous.max(best)));
+            }
+            if history.len() < iterations {
+                self.migrate();
+            }
+        }
+
+        let (best_path, _) = self.colonies.iter()
+            .filter_map(|colony| colony.best())
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .ok_or(SolverError::NoIterations)?;
+        Ok(RunResult {
+            solver: self.name(),
+            best: Solution::new(best_path.to_vec(), &self.dishes, &self.problem),
+            history,
+            found_in: None,
+            iterations: vec![],
+            diagnostics: vec![],
+            baseline: self.colonies[0].baseline(),
+            seed: self.seed,
+            evaluations: self.colonies.iter().map(AntColony::evaluations).sum(),
+            elapsed: started_at.elapsed(),
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use genevo::genetic::{Children, Parents};
+use genevo::operator::{CrossoverOp, GeneticOperator};
+use genevo::prelude::Rng;
+use rand::seq::SliceRandom;
+use crate::dish::Dish;
+use crate::problem::{DietProblem, DietTotals};
+
+/// Builds every child dish by dish, in a random order, taking the servings of the
+/// dish from whichever of two parents brings the child's totals closer to the
+/// nutrient targets without going over budget
+#[derive(Clone, Debug)]
+pub struct NutrientCrossover<'a> {
+    pub dishes: &'a [Dish],
+    pub problem: &'a DietProblem,
+}
+
+impl NutrientCrossover<'_> {
+    /// How far the totals would be from the targets with `count` more servings of `dish`,
+    /// infinitely far when over budget
+    fn distance(&self, totals: &DietTotals, dish: usize, count: u32) -> f64 {
+        let mut totals = *totals;
+        totals.add(&self.dishes[dish], count);
+        if totals.price > self.problem.budget as u64 {
+            f64::INFINITY
+        } else {
+            self.problem.target_distance(&totals)
+        }
+    }
+}
+
+impl GeneticOperator for NutrientCrossover<'_> {
+    fn name() -> String {
+        "Nutrient-Cross-Breeder".to_string()
+    }
+}
+
+impl CrossoverOp<Vec<u32>> for NutrientCrossover<'_> {
+    /// Breeds one child per parent, each parent with the next one of the tuple
+    fn crossover<R>(&self, parents: Parents<Vec<u32>>, rng: &mut R) -> Children<Vec<u32>>
+        where
+            R: Rng + Sized,
+    {
+        let mut order: Vec<usize> = (0..self.dishes.len()).collect();
+        (0..parents.len())
+            .map(|index| {
+                let (father, mother) = (&parents[index], &parents[(index + 1) % parents.len()]);
+                order.shuffle(rng);
+                let mut child = vec![0; self.dishes.len()];
+                let mut totals = DietTotals::default();
+                for &dish in &order {
+                    let (a, b) = (father[dish], mother[dish]);
+                    let (distance_a, distance_b) = (self.distance(&totals, dish, a), self.distance(&totals, dish, b));
+                    // neither parent fits the budget any more, the child takes the smaller portion
+                    child[dish] = if distance_a.is_infinite() && distance_b.is_infinite() {
+                        a.min(b)
+                    } else if distance_a == distance_b {
+                        if rng.gen() { a } else { b }
+                    } else if distance_a < distance_b {
+                        a
+                    } else {
+                        b
+                    };
+                    totals.add(&self.dishes[dish], child[dish]);
+                }
+                child
+            })
+            .collect()
+    }
+}
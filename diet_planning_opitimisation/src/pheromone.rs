@@ -0,0 +1,228 @@
+use std::error::Error;
+use std::path::Path;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use crate::dish::Dish;
+
+/// What the colony attaches its pheromone to
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PheromoneModel {
+    /// One trail per dish
+    Dish,
+    /// One trail per (previous dish, next dish) pair, so the colony can learn
+    /// which foods combine well
+    Transition,
+    /// One trail per (dish, servings) pair; ants decide on a dish and its portion
+    /// at once, so the colony learns good quantities
+    Portion,
+}
+
+/// Pheromone levels laid out according to a `PheromoneModel`, followed by the
+/// trails of the "stop here" choice
+#[derive(Debug, Clone)]
+pub struct Trails {
+    model: PheromoneModel,
+    dishes: usize,
+    max_servings: usize,
+    /// Index of the first stop trail
+    stop_offset: usize,
+    levels: Vec<f64>,
+}
+
+impl Trails {
+    pub fn new(model: PheromoneModel, dishes: usize, max_servings: usize, level: f64) -> Self {
+        let len = match model {
+            PheromoneModel::Dish => dishes,
+            // the extra row holds the trails leaving the start of a path
+            PheromoneModel::Transition => (dishes + 1) * dishes,
+            PheromoneModel::Portion => dishes * max_servings,
+        };
+        // transitions learn when to stop after each dish, the other models once for the whole path
+        let stops = match model {
+            PheromoneModel::Transition => dishes + 1,
+            PheromoneModel::Dish | PheromoneModel::Portion => 1,
+        };
+        Self { model, dishes, max_servings, stop_offset: len, levels: vec![level; len + stops] }
+    }
+
+    pub fn model(&self) -> PheromoneModel {
+        self.model
+    }
+
+    /// How many alternatives an ant weighs against each other at every step
+    pub fn choices(&self) -> usize {
+        match self.model {
+            PheromoneModel::Dish | PheromoneModel::Transition => self.dishes,
+            PheromoneModel::Portion => self.dishes * self.max_servings,
+        }
+    }
+
+    /// How many rows of choices there are, transitions have one per previous dish
+    /// and one for the start of a path
+    pub fn rows(&self) -> usize {
+        match self.model {
+            PheromoneModel::Dish | PheromoneModel::Portion => 1,
+            PheromoneModel::Transition => self.dishes + 1,
+        }
+    }
+
+    /// The row of choices open after `previous`, which is `None` for the first dish of a path
+    pub fn row(&self, previous: Option<usize>) -> usize {
+        match self.model {
+            PheromoneModel::Dish | PheromoneModel::Portion => 0,
+            PheromoneModel::Transition => previous.unwrap_or(self.dishes),
+        }
+    }
+
+    /// The (dish, servings) pair behind a choice
+    pub fn choice(&self, choice: usize) -> (usize, usize) {
+        match self.model {
+            PheromoneModel::Dish | PheromoneModel::Transition => (choice, 1),
+            PheromoneModel::Portion => (choice / self.max_servings, choice % self.max_servings + 1),
+        }
+    }
+
+    /// Inverse of `choice`
+    pub fn choice_of(&self, dish: usize, servings: usize) -> usize {
+        match self.model {
+            PheromoneModel::Dish | PheromoneModel::Transition => dish,
+            PheromoneModel::Portion => dish * self.max_servings + servings - 1,
+        }
+    }
+
+    /// The trail followed when an ant adds `servings` of `dish` after `previous`
+    pub fn index(&self, previous: Option<usize>, dish: usize, servings: usize) -> usize {
+        self.row(previous) * self.choices() + self.choice_of(dish, servings)
+    }
+
+    /// Levels of every choice of a row, indexed by choice
+    pub fn row_levels(&self, row: usize) -> &[f64] {
+        let choices = self.choices();
+        &self.levels[row * choices..(row + 1) * choices]
+    }
+
+    pub fn level(&self, previous: Option<usize>, dish: usize, servings: usize) -> f64 {
+        self.levels[self.index(previous, dish, servings)]
+    }
+
+    /// The trail followed when an ant ends its path after `previous`
+    pub fn stop_index(&self, previous: Option<usize>) -> usize {
+        match self.model {
+            PheromoneModel::Transition => self.stop_offset + previous.unwrap_or(self.dishes),
+            PheromoneModel::Dish | PheromoneModel::Portion => self.stop_offset,
+        }
+    }
+
+    pub fn stop_level(&self, previous: Option<usize>) -> f64 {
+        self.levels[self.stop_index(previous)]
+    }
+
+    pub fn levels_mut(&mut self) -> &mut [f64] {
+        &mut self.levels
+    }
+
+    /// Lets the share `rho` of every trail evaporate
+    pub fn evaporate(&mut self, rho: f64) {
+        for level in &mut self.levels {
+            *level *= 1.0 - rho;
+        }
+    }
+
+    pub fn levels(&self) -> &[f64] {
+        &self.levels
+    }
+
+    /// Name of every trail, in the order of the levels
+    pub fn labels(&self, dishes: &[Dish]) -> Vec<String> {
+        let choices = self.choices();
+        let after = |row: usize| if row == self.dishes { "start" } else { dishes[row].name.as_str() };
+        let moves = (0..self.stop_offset).map(|index| {
+            let (dish, servings) = self.choice(index % choices);
+            match self.model {
+                PheromoneModel::Dish => dishes[dish].name.clone(),
+                PheromoneModel::Transition => format!("{} -> {}", after(index / choices), dishes[dish].name),
+                PheromoneModel::Portion => format!("{} x {}", servings, dishes[dish].name),
+            }
+        });
+        let stops = (self.stop_offset..self.levels.len()).map(|index| match self.model {
+            PheromoneModel::Transition => format!("{} -> stop", after(index - self.stop_offset)),
+            PheromoneModel::Dish | PheromoneModel::Portion => "stop".to_string(),
+        });
+        moves.chain(stops).collect()
+    }
+
+    /// Pheromone leading to each dish in `levels`, summed over its portions or predecessors
+    pub fn dish_levels(&self, levels: &[f64]) -> Vec<f64> {
+        let mut dish_levels = vec![0.0; self.dishes];
+        for (index, level) in levels[..self.stop_offset].iter().enumerate() {
+            dish_levels[self.choice(index % self.choices()).0] += level;
+        }
+        dish_levels
+    }
+
+    /// Shannon entropy of the choices of every row, normalised to [0, 1] and averaged over the rows
+    pub fn entropy(&self) -> f64 {
+        let choices = self.choices();
+        if choices < 2 {
+            return 0.0;
+        }
+        let total: f64 = (0..self.rows())
+            .map(|row| {
+                let levels = self.row_levels(row);
+                let sum: f64 = levels.iter().sum();
+                let entropy: f64 = levels.iter()
+                    .map(|level| level / sum)
+                    .filter(|&share| share > 0.0)
+                    .map(|share| -share * share.ln())
+                    .sum();
+                entropy / (choices as f64).ln()
+            })
+            .sum();
+        total / self.rows() as f64
+    }
+
+    /// Dorigo's lambda-branching factor averaged over the rows: how many choices have
+    /// a level above `min + lambda * (max - min)` of their row
+    pub fn branching_factor(&self, lambda: f64) -> f64 {
+        let total: usize = (0..self.rows())
+            .map(|row| {
+                let levels = self.row_levels(row);
+                let min = levels.iter().cloned().fold(f64::MAX, f64::min);
+                let max = levels.iter().cloned().fold(f64::MIN, f64::max);
+                let threshold = min + lambda * (max - min);
+                levels.iter().filter(|&&level| level >= threshold).count()
+            })
+            .sum();
+        total as f64 / self.rows() as f64
+    }
+
+    /// Adds `amount` once to every distinct trail in `trail`
+    pub fn deposit(&mut self, trail: &[usize], amount: f64) {
+        let mut trail = trail.to_vec();
+        trail.sort_unstable();
+        trail.dedup();
+        for index in trail {
+            self.levels[index] += amount;
+        }
+    }
+}
+
+/// Pheromone levels of every trail after an iteration
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub iteration: usize,
+    pub levels: Vec<f64>,
+}
+
+/// Writes one row per snapshot and one column per trail
+pub fn write_snapshots(snapshots: &[Snapshot], labels: &[String], path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(std::iter::once("iteration").chain(labels.iter().map(String::as_str)))?;
+    for snapshot in snapshots {
+        let levels = snapshot.levels.iter().map(|level| level.to_string());
+        writer.write_record(std::iter::once(snapshot.iteration.to_string()).chain(levels))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
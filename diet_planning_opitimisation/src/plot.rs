@@ -0,0 +1,122 @@
+use std::error::Error;
+use std::path::Path;
+use plotters::prelude::*;
+use crate::solver::RunResult;
+
+/// Best fitness over iterations, one line per result
+pub fn plot_convergence(results: &[RunResult], path: &Path) -> Result<(), Box<dyn Error>> {
+    let root = BitMapBackend::new(path, (640, 480)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let values = results.iter().flat_map(|result| result.history.iter().cloned());
+    let min_fitness = values.clone().fold(f64::MAX, f64::min);
+    let max_fitness = values.fold(f64::MIN, f64::max);
+    let (min_fitness, max_fitness) = if min_fitness < max_fitness {
+        (min_fitness, max_fitness)
+    } else {
+        (min_fitness - 1.0, max_fitness + 1.0)
+    };
+    let max_iterations = results.iter().map(|result| result.history.len()).max().unwrap_or(0);
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Best Fitness Over Generations", ("sans-serif", 20).into_font())
+        .margin(25)
+        .x_label_area_size(30)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0f64..max_iterations as f64, min_fitness..max_fitness)?;
+
+    chart.configure_mesh().draw()?;
+
+    for (index, result) in results.iter().enumerate() {
+        let color = Palette99::pick(index).to_rgba();
+        chart.draw_series(LineSeries::new(
+            result.history.iter().enumerate().map(|(i, fitness)| (i as f64, *fitness)),
+            &color,
+        ))?
+            .label(result.solver.clone())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+
+    chart.configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+    root.present()?;
+    Ok(())
+}
+
+/// Colour of a share of the pheromone, from blue for none to red for all of it
+fn share_color(share: f64) -> HSLColor {
+    HSLColor((1.0 - share.clamp(0.0, 1.0)) * 2.0 / 3.0, 0.9, 0.5)
+}
+
+/// Share of the pheromone on every dish (rows) at every snapshot (columns)
+pub fn plot_pheromone_heatmap(names: &[String], iterations: &[usize], shares: &[Vec<f64>], path: &Path) -> Result<(), Box<dyn Error>> {
+    let height = (names.len() as u32 * 20 + 100).max(480);
+    let root = BitMapBackend::new(path, (1000, height)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let max_share = shares.iter().flatten().cloned().fold(f64::MIN_POSITIVE, f64::max);
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Pheromone share per dish", ("sans-serif", 20).into_font())
+        .margin(25)
+        .x_label_area_size(30)
+        .y_label_area_size(200)
+        .build_cartesian_2d((0..iterations.len()).into_segmented(), (0..names.len()).into_segmented())?;
+
+    chart.configure_mesh()
+        .disable_mesh()
+        .x_desc("iteration")
+        .x_label_formatter(&|x| match x {
+            SegmentValue::CenterOf(i) => iterations.get(*i).map(|iteration| iteration.to_string()).unwrap_or_default(),
+            _ => String::new(),
+        })
+        .y_labels(names.len())
+        .y_label_formatter(&|y| match y {
+            SegmentValue::CenterOf(i) => names.get(*i).cloned().unwrap_or_default(),
+            _ => String::new(),
+        })
+        .draw()?;
+
+    chart.draw_series(shares.iter().enumerate().flat_map(|(x, column)| {
+        column.iter().enumerate().map(move |(y, share)| {
+            Rectangle::new(
+                [(SegmentValue::Exact(x), SegmentValue::Exact(y)), (SegmentValue::Exact(x + 1), SegmentValue::Exact(y + 1))],
+                share_color(share / max_share).filled(),
+            )
+        })
+    }))?;
+    root.present()?;
+    Ok(())
+}
+
+/// One frame per snapshot with the share of the pheromone on every dish
+pub fn animate_pheromone(names: &[String], iterations: &[usize], shares: &[Vec<f64>], path: &Path) -> Result<(), Box<dyn Error>> {
+    let root = BitMapBackend::gif(path, (800, 480), 300)?.into_drawing_area();
+    let max_share = shares.iter().flatten().cloned().fold(f64::MIN_POSITIVE, f64::max);
+
+    for (iteration, column) in iterations.iter().zip(shares) {
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption(format!("Pheromone after iteration {}", iteration), ("sans-serif", 20).into_font())
+            .margin(25)
+            .x_label_area_size(30)
+            .y_label_area_size(50)
+            .build_cartesian_2d((0..names.len()).into_segmented(), 0f64..max_share)?;
+
+        chart.configure_mesh()
+            .disable_x_mesh()
+            .x_desc("dish")
+            .y_desc("share of the pheromone")
+            .draw()?;
+
+        chart.draw_series(column.iter().enumerate().map(|(dish, share)| {
+            Rectangle::new(
+                [(SegmentValue::Exact(dish), 0.0), (SegmentValue::Exact(dish + 1), *share)],
+                share_color(share / max_share).filled(),
+            )
+        }))?;
+        root.present()?;
+    }
+    Ok(())
+}
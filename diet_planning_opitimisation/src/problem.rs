@@ -0,0 +1,213 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::dish::Dish;
+
+/// Daily amounts of the macro nutrients we plan for
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Nutrients {
+    pub calories: u32,
+    pub carbs: u32,
+    pub fats: u32,
+    pub proteins: u32,
+}
+
+/// Price and nutrients summed over every serving of a diet, wide enough that the
+/// `u32` values of a catalog can't overflow them
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DietTotals {
+    pub price: u64,
+    pub calories: u64,
+    pub carbs: u64,
+    pub fats: u64,
+    pub proteins: u64,
+}
+
+impl DietTotals {
+    /// Totals of a diet given as (dish index, servings) pairs
+    pub fn of<I>(dishes: &[Dish], servings: I) -> Self
+        where
+            I: IntoIterator<Item = (usize, u32)>,
+    {
+        let mut totals = DietTotals::default();
+        for (dish, count) in servings {
+            totals.add(&dishes[dish], count);
+        }
+        totals
+    }
+
+    pub fn add(&mut self, dish: &Dish, count: u32) {
+        let count = count as u64;
+        self.price += dish.price as u64 * count;
+        self.calories += dish.calories as u64 * count;
+        self.carbs += dish.carbs as u64 * count;
+        self.fats += dish.fats as u64 * count;
+        self.proteins += dish.proteins as u64 * count;
+    }
+
+    pub fn remove(&mut self, dish: &Dish, count: u32) {
+        let count = count as u64;
+        self.price -= dish.price as u64 * count;
+        self.calories -= dish.calories as u64 * count;
+        self.carbs -= dish.carbs as u64 * count;
+        self.fats -= dish.fats as u64 * count;
+        self.proteins -= dish.proteins as u64 * count;
+    }
+}
+
+/// The objective of a diet split into the parts it is made of
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ObjectiveBreakdown {
+    /// Every nutrient normalised by its target, summed up
+    pub nutrients: f64,
+    /// Price normalised by the budget
+    pub price: f64,
+    /// Absolute deviation of every nutrient outside its tolerance band plus any overspending
+    pub penalty: f64,
+}
+
+impl ObjectiveBreakdown {
+    pub fn total(&self) -> f64 {
+        self.nutrients - self.price - self.penalty
+    }
+}
+
+/// The diet planning problem shared by every solver: nutrient targets with the
+/// tolerance band accepted around each of them, the budget and the serving cap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DietProblem {
+    pub targets: Nutrients,
+    /// Allowed deviation from each target before the diet gets penalised
+    pub tolerances: Nutrients,
+    /// Price limit in zloty * 10, the same unit as `Dish::price`
+    pub budget: u32,
+    /// How many servings of a single dish a diet may contain
+    pub max_servings: u32,
+}
+
+impl Default for DietProblem {
+    fn default() -> Self {
+        Self {
+            targets: Nutrients { calories: 2250, carbs: 275, fats: 50, proteins: 120 },
+            tolerances: Nutrients { calories: 200, carbs: 25, fats: 10, proteins: 20 },
+            budget: 200, // 20 zloty
+            max_servings: 10,
+        }
+    }
+}
+
+/// A `DietProblem` the solvers can't work with, they divide by every target and the budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidProblem(pub String);
+
+impl fmt::Display for InvalidProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid problem: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidProblem {}
+
+impl DietProblem {
+    pub fn validate(&self) -> Result<(), InvalidProblem> {
+        let targets = &self.targets;
+        let positive = [
+            (targets.calories, "the calorie target"),
+            (targets.carbs, "the carbohydrate target"),
+            (targets.fats, "the fat target"),
+            (targets.proteins, "the protein target"),
+            (self.budget, "the budget"),
+            (self.max_servings, "max_servings"),
+        ];
+        match positive.iter().find(|(value, _)| *value == 0) {
+            Some((_, name)) => Err(InvalidProblem(format!("{} must be at least 1", name))),
+            None => Ok(()),
+        }
+    }
+
+    /// (total, target, tolerance) for each nutrient
+    fn nutrient_bands(&self, totals: &DietTotals) -> [(u64, u64, u64); 4] {
+        let (targets, tolerances) = (&self.targets, &self.tolerances);
+        [
+            (totals.calories, targets.calories as u64, tolerances.calories as u64),
+            (totals.carbs, targets.carbs as u64, tolerances.carbs as u64),
+            (totals.fats, targets.fats as u64, tolerances.fats as u64),
+            (totals.proteins, targets.proteins as u64, tolerances.proteins as u64),
+        ]
+    }
+
+    /// Whether `servings` more of `dish` keep the diet within the budget and below
+    /// the upper end of every tolerance band
+    pub fn fits(&self, totals: &DietTotals, dish: &Dish, servings: u32) -> bool {
+        let mut totals = *totals;
+        totals.add(dish, servings);
+        self.within_limits(&totals)
+    }
+
+    /// Whether the diet stays within the budget and below the upper end of every tolerance band
+    pub fn within_limits(&self, totals: &DietTotals) -> bool {
+        totals.price <= self.budget as u64
+            && self.nutrient_bands(totals).iter().all(|&(total, target, tolerance)| total <= target + tolerance)
+    }
+
+    /// Whether `dish` adds to the price or to a nutrient the diet has too much of
+    pub fn adds_to_excess(&self, totals: &DietTotals, dish: &Dish) -> bool {
+        let mut serving = DietTotals::default();
+        serving.add(dish, 1);
+        (totals.price > self.budget as u64 && dish.price > 0)
+            || self.nutrient_bands(totals).iter().zip(self.nutrient_bands(&serving))
+                .any(|(&(total, target, tolerance), (amount, _, _))| total > target + tolerance && amount > 0)
+    }
+
+    /// Share of the gap still left below each target that `servings` more of `dish`
+    /// would close, summed over the nutrients
+    pub fn gap_closed(&self, totals: &DietTotals, dish: &Dish, servings: u32) -> f64 {
+        let targets = &self.targets;
+        let closed = |amount: u32, total: u64, target: u32| {
+            (amount as u64 * servings as u64).min((target as u64).saturating_sub(total)) as f64 / target as f64
+        };
+        closed(dish.calories, totals.calories, targets.calories)
+            + closed(dish.carbs, totals.carbs, targets.carbs)
+            + closed(dish.fats, totals.fats, targets.fats)
+            + closed(dish.proteins, totals.proteins, targets.proteins)
+    }
+
+    /// Nutrients of a serving of `dish` normalised by their targets, per unit of price
+    pub fn nutrient_density(&self, dish: &Dish) -> f64 {
+        let targets = &self.targets;
+        let nutrition = dish.calories as f64 / targets.calories as f64
+            + dish.carbs as f64 / targets.carbs as f64
+            + dish.fats as f64 / targets.fats as f64
+            + dish.proteins as f64 / targets.proteins as f64;
+        nutrition / dish.price as f64
+    }
+
+    /// Deviation of every nutrient from its target relative to the target, summed up
+    pub fn target_distance(&self, totals: &DietTotals) -> f64 {
+        self.nutrient_bands(totals).iter().map(|&(total, target, _)| total.abs_diff(target) as f64 / target as f64).sum()
+    }
+
+    /// Whether every nutrient reached at least the lower end of its tolerance band
+    pub fn targets_met(&self, totals: &DietTotals) -> bool {
+        self.nutrient_bands(totals).iter().all(|&(total, target, tolerance)| total + tolerance >= target)
+    }
+
+    /// The objective maximised by both algorithms. Nutrients normalised by their
+    /// targets are rewarded and the normalised price is subtracted; every nutrient
+    /// outside its tolerance band and any overspending is penalised by its absolute deviation.
+    pub fn objective(&self, totals: &DietTotals) -> f64 {
+        self.breakdown(totals).total()
+    }
+
+    /// The parts `objective` sums up
+    pub fn breakdown(&self, totals: &DietTotals) -> ObjectiveBreakdown {
+        let mut breakdown = ObjectiveBreakdown { price: totals.price as f64 / self.budget as f64, ..Default::default() };
+        for (total, target, tolerance) in self.nutrient_bands(totals) {
+            breakdown.nutrients += total as f64 / target as f64;
+            if total.abs_diff(target) > tolerance {
+                breakdown.penalty += total.abs_diff(target) as f64;
+            }
+        }
+        breakdown.penalty += totals.price.saturating_sub(self.budget as u64) as f64;
+        breakdown
+    }
+}
@@ -0,0 +1,111 @@
+use genevo::operator::{GeneticOperator, MutationOp};
+use genevo::prelude::Rng;
+use crate::dish::Dish;
+use crate::dynamic_operators::DynamicMutator;
+use crate::greedy::best_gap_filler;
+use crate::problem::{DietProblem, DietTotals};
+
+/// Moves a count genotype towards a feasible diet: servings are dropped while the
+/// diet is over budget or above a tolerance band, the one giving the least
+/// nutrition per price first, then the best value gap fillers are added until the
+/// targets are met or nothing fits any more
+#[derive(Clone, Debug)]
+pub struct Repair<'a> {
+    pub dishes: &'a [Dish],
+    pub problem: &'a DietProblem,
+    /// Probability of repairing each genome
+    pub rate: f64,
+    pub max_value: u32,
+}
+
+impl Repair<'_> {
+    pub fn repair(&self, mut genome: Vec<u32>) -> Vec<u32> {
+        let mut totals = DietTotals::of(self.dishes, genome.iter().copied().enumerate());
+        while !self.problem.within_limits(&totals) {
+            let worst = (0..self.dishes.len())
+                .filter(|&dish| genome[dish] > 0 && self.problem.adds_to_excess(&totals, &self.dishes[dish]))
+                .min_by(|&a, &b| {
+                    let density = |dish: usize| self.problem.nutrient_density(&self.dishes[dish]);
+                    density(a).partial_cmp(&density(b)).unwrap()
+                });
+            let Some(dish) = worst else {
+                break;
+            };
+            genome[dish] -= 1;
+            totals.remove(&self.dishes[dish], 1);
+        }
+        while !self.problem.targets_met(&totals) {
+            let Some(dish) = best_gap_filler(self.dishes, self.problem, &totals, |dish| genome[dish] < self.max_value) else {
+                break;
+            };
+            genome[dish] += 1;
+            totals.add(&self.dishes[dish], 1);
+        }
+        genome
+    }
+
+}
+
+/// Mutates with `mutator`, then repairs the child with the probability of `repair`
+#[derive(Clone, Debug)]
+pub struct RepairingMutator<'a> {
+    pub mutator: DynamicMutator,
+    /// Children are left as they are when unset, the penalties of the objective
+    /// are then the only constraint handling
+    pub repair: Option<Repair<'a>>,
+}
+
+impl GeneticOperator for RepairingMutator<'_> {
+    fn name() -> String {
+        "Repairing-Mutation".to_string()
+    }
+}
+
+impl MutationOp<Vec<u32>> for RepairingMutator<'_> {
+    fn mutate<R>(&self, genome: Vec<u32>, rng: &mut R) -> Vec<u32>
+        where
+            R: Rng + Sized,
+    {
+        let genome = self.mutator.mutate(genome, rng);
+        match &self.repair {
+            Some(repair) if rng.gen::<f64>() < repair.rate => repair.repair(genome),
+            _ => genome,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::dish::get_dishes;
+    use super::*;
+
+    fn repaired(dishes: &[Dish], problem: &DietProblem, genome: Vec<u32>) -> DietTotals {
+        let repair = Repair { dishes, problem, rate: 1.0, max_value: problem.max_servings };
+        let genome = repair.repair(genome);
+        assert!(genome.iter().all(|&gene| gene <= problem.max_servings));
+        DietTotals::of(dishes, genome.into_iter().enumerate())
+    }
+
+    #[test]
+    fn repair_brings_overfull_diets_within_limits() {
+        let (dishes, problem) = (get_dishes(), DietProblem::default());
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut genomes = vec![vec![problem.max_servings; dishes.len()]];
+        genomes.extend((0..50).map(|_| (0..dishes.len()).map(|_| rng.gen_range(0..=problem.max_servings)).collect()));
+        genomes.retain(|genome| !problem.within_limits(&DietTotals::of(&dishes, genome.iter().copied().enumerate())));
+        assert!(genomes.len() > 1);
+        for genome in genomes {
+            assert!(problem.within_limits(&repaired(&dishes, &problem, genome)));
+        }
+    }
+
+    #[test]
+    fn repair_fills_an_empty_diet_up_to_the_targets() {
+        let (dishes, problem) = (get_dishes(), DietProblem::default());
+        let totals = repaired(&dishes, &problem, vec![0; dishes.len()]);
+        assert!(problem.within_limits(&totals));
+        assert!(problem.targets_met(&totals));
+    }
+}
@@ -0,0 +1,99 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use crate::cli::{OutputArgs, OutputFormat};
+use crate::dish::Dish;
+use crate::pheromone::{write_snapshots, Snapshot, Trails};
+use crate::plot::{animate_pheromone, plot_convergence, plot_pheromone_heatmap};
+use crate::problem::DietProblem;
+use crate::solver::RunResult;
+
+pub fn print_report(result: &RunResult, dishes: &[Dish], problem: &DietProblem) {
+    let best = &result.best;
+    let (targets, tolerances) = (&problem.targets, &problem.tolerances);
+
+    println!("Solver: {}", result.solver);
+    println!("Goal function value: {:.4}", best.objective);
+    println!(
+        "Objective: nutrients {:.4} - price {:.4} - penalty {:.4}",
+        best.breakdown.nutrients, best.breakdown.price, best.breakdown.penalty,
+    );
+    if let Some(found_in) = result.found_in {
+        println!("Found in iteration {} of {}", found_in, result.history.len());
+    }
+    println!("Evaluations: {} in {:.2?}", result.evaluations, result.elapsed);
+    println!("Seed: {}", result.seed);
+    if let Some(baseline) = result.baseline {
+        println!("Greedy baseline: {:.4}", baseline);
+    }
+    if let Some(last) = result.iterations.last() {
+        println!("Last iteration: best {:.4}, mean {:.4}, worst {:.4}", last.best, last.mean, last.worst);
+    }
+    if let Some(last) = result.diagnostics.last() {
+        println!(
+            "Last iteration: entropy {:.3}, branching factor {:.2}, {} distinct diets",
+            last.entropy, last.branching_factor, last.distinct_paths,
+        );
+    }
+    println!("Best diet:");
+    for dish_count in &best.diet {
+        println!("  {:>3} x {}", dish_count.count, dishes[dish_count.dish].name);
+    }
+    println!("Total price: {} (budget {})", best.totals.price, problem.budget);
+    println!("Total calories: {} (target {} ± {})", best.totals.calories, targets.calories, tolerances.calories);
+    println!("Total carbs: {} (target {} ± {})", best.totals.carbs, targets.carbs, tolerances.carbs);
+    println!("Total fats: {} (target {} ± {})", best.totals.fats, targets.fats, tolerances.fats);
+    println!("Total proteins: {} (target {} ± {})", best.totals.proteins, targets.proteins, tolerances.proteins);
+}
+
+/// Prints the throughput of a colony run started by `diet bench`
+pub fn print_benchmark(result: &RunResult, dishes: &[Dish]) {
+    let seconds = result.elapsed.as_secs_f64();
+    println!("Dishes: {}", dishes.len());
+    println!("Ant paths: {} in {:.2?}", result.evaluations, result.elapsed);
+    println!("Ants per second: {:.0}", result.evaluations as f64 / seconds);
+    println!("Goal function value: {:.4}", result.best.objective);
+}
+
+/// Prints the result in the requested format and, when an output directory was
+/// given, saves it there as JSON together with its convergence plot
+pub fn emit(result: &RunResult, dishes: &[Dish], problem: &DietProblem, output: &OutputArgs) -> Result<(), Box<dyn Error>> {
+    let json = serde_json::to_string_pretty(result)?;
+    match output.format {
+        OutputFormat::Report => print_report(result, dishes, problem),
+        OutputFormat::Json => println!("{}", json),
+    }
+
+    if let Some(output_dir) = &output.output_dir {
+        fs::create_dir_all(output_dir)?;
+        let stem = result.solver.replace(' ', "_");
+        fs::write(output_dir.join(format!("{}_result.json", stem)), json)?;
+        plot_convergence(std::slice::from_ref(result), &output_dir.join(format!("{}_convergence.png", stem)))?;
+    }
+    Ok(())
+}
+
+/// Saves the pheromone snapshots of a colony as CSV, together with a heatmap and an
+/// animation of the share of the pheromone leading to each dish
+pub fn save_pheromone(trails: &Trails, snapshots: &[Snapshot], dishes: &[Dish], output_dir: &Path) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(output_dir)?;
+    write_snapshots(snapshots, &trails.labels(dishes), &output_dir.join("pheromone.csv"))?;
+
+    let names: Vec<String> = dishes.iter().map(|dish| dish.name.clone()).collect();
+    let iterations: Vec<usize> = snapshots.iter().map(|snapshot| snapshot.iteration).collect();
+    let shares: Vec<Vec<f64>> = snapshots.iter()
+        .map(|snapshot| {
+            let levels = trails.dish_levels(&snapshot.levels);
+            let total: f64 = levels.iter().sum();
+            levels.iter().map(|level| level / total).collect()
+        })
+        .collect();
+    plot_pheromone_heatmap(&names, &iterations, &shares, &output_dir.join("pheromone_heatmap.png"))?;
+    animate_pheromone(&names, &iterations, &shares, &output_dir.join("pheromone.gif"))?;
+    Ok(())
+}
+
+pub fn load_result(path: &Path) -> Result<RunResult, Box<dyn Error>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
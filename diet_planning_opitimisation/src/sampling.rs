@@ -0,0 +1,131 @@
+use rand::Rng;
+
+/// Walker's alias table, draws an index proportionally to its weight in O(1)
+#[derive(Debug, Clone)]
+pub struct AliasTable {
+    weights: Vec<f64>,
+    total: f64,
+    /// Probability of keeping the drawn column instead of jumping to its alias
+    keep: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl AliasTable {
+    /// Builds the table with Vose's method, `weights` must not be empty. When they
+    /// sum up to zero every index is drawn equally often.
+    pub fn new(weights: Vec<f64>) -> Self {
+        let len = weights.len();
+        let total: f64 = weights.iter().sum();
+        let mut keep: Vec<f64> = if total > 0.0 {
+            weights.iter().map(|weight| weight * len as f64 / total).collect()
+        } else {
+            vec![1.0; len]
+        };
+        let mut alias: Vec<usize> = (0..len).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..len).partition(|&i| keep[i] < 1.0);
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            alias[less] = more;
+            keep[more] -= 1.0 - keep[less];
+            if keep[more] < 1.0 {
+                large.pop();
+                small.push(more);
+            }
+        }
+        // whatever is left only misses 1.0 by rounding errors
+        for i in small.into_iter().chain(large) {
+            keep[i] = 1.0;
+        }
+        Self { weights, total, keep, alias }
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> usize {
+        let column = rng.gen_range(0..self.keep.len());
+        if rng.gen::<f64>() < self.keep[column] {
+            column
+        } else {
+            self.alias[column]
+        }
+    }
+
+    pub fn weight(&self, index: usize) -> f64 {
+        self.weights[index]
+    }
+
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+}
+
+/// Draws an index proportionally to the weights summed up in `cumulative`
+pub fn sample_cumulative<R: Rng>(cumulative: &[f64], rng: &mut R) -> Option<usize> {
+    let total = *cumulative.last()?;
+    let target = rng.gen::<f64>() * total;
+    Some(cumulative.partition_point(|&sum| sum <= target).min(cumulative.len() - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use super::*;
+
+    const DRAWS: usize = 100_000;
+
+    /// Share of `DRAWS` draws that hit each of `len` indices
+    fn frequencies<F: FnMut(&mut StdRng) -> usize>(len: usize, mut draw: F) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut counts = vec![0; len];
+        for _ in 0..DRAWS {
+            counts[draw(&mut rng)] += 1;
+        }
+        counts.into_iter().map(|count| count as f64 / DRAWS as f64).collect()
+    }
+
+    fn assert_close(frequencies: &[f64], expected: &[f64]) {
+        for (index, (frequency, expected)) in frequencies.iter().zip(expected).enumerate() {
+            assert!((frequency - expected).abs() < 0.01, "index {} drawn {} of the time, expected {}", index, frequency, expected);
+        }
+    }
+
+    #[test]
+    fn alias_table_draws_proportionally_to_the_weights() {
+        let weights = vec![1.0, 2.0, 0.0, 3.0, 4.0];
+        let table = AliasTable::new(weights.clone());
+        assert_eq!(table.total(), 10.0);
+        assert_eq!(table.weight(3), 3.0);
+        let frequencies = frequencies(weights.len(), |rng| table.sample(rng));
+        assert_eq!(frequencies[2], 0.0);
+        assert_close(&frequencies, &[0.1, 0.2, 0.0, 0.3, 0.4]);
+    }
+
+    #[test]
+    fn alias_table_of_zero_weights_draws_uniformly() {
+        let table = AliasTable::new(vec![0.0; 4]);
+        assert_eq!(table.total(), 0.0);
+        assert_close(&frequencies(4, |rng| table.sample(rng)), &[0.25; 4]);
+    }
+
+    #[test]
+    fn alias_table_of_one_entry_always_draws_it() {
+        let table = AliasTable::new(vec![0.5]);
+        assert_eq!(frequencies(1, |rng| table.sample(rng)), vec![1.0]);
+    }
+
+    #[test]
+    fn cumulative_sampling_draws_proportionally_to_the_weights() {
+        // weights 0, 2, 0, 3
+        let cumulative = [0.0, 2.0, 2.0, 5.0];
+        let frequencies = frequencies(cumulative.len(), |rng| sample_cumulative(&cumulative, rng).unwrap());
+        assert_eq!(frequencies[0], 0.0);
+        assert_eq!(frequencies[2], 0.0);
+        assert_close(&frequencies, &[0.0, 0.4, 0.0, 0.6]);
+    }
+
+    #[test]
+    fn cumulative_sampling_of_nothing_draws_nothing() {
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(sample_cumulative(&[], &mut rng), None);
+        assert_eq!(sample_cumulative(&[4.0], &mut rng), Some(0));
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::dish::Dish;
+use crate::problem::{DietProblem, DietTotals, ObjectiveBreakdown};
+
+/// How many servings of the dish at index `dish` of the catalog a diet contains
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DishCount {
+    pub(crate) dish: usize,
+    pub(crate) count: usize,
+}
+
+/// A diet found by a solver, evaluated against the `DietProblem` it was solving
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Solution {
+    pub diet: Vec<DishCount>,
+    pub totals: DietTotals,
+    pub objective: f64,
+    /// What the objective is made of
+    #[serde(default)]
+    pub breakdown: ObjectiveBreakdown,
+}
+
+impl Solution {
+    pub fn new(diet: Vec<DishCount>, dishes: &[Dish], problem: &DietProblem) -> Self {
+        let totals = DietTotals::of(dishes, diet.iter().map(|dish_count| (dish_count.dish, dish_count.count as u32)));
+        let breakdown = problem.breakdown(&totals);
+        Self {
+            objective: breakdown.total(),
+            breakdown,
+            totals,
+            diet,
+        }
+    }
+}
+
+/// Convergence diagnostics of one colony iteration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostics {
+    /// Shannon entropy of the pheromone, 1 while the trails are uniform and 0 once a single one holds all of it
+    pub entropy: f64,
+    /// Average lambda-branching factor, how many choices per decision still carry noticeable pheromone
+    pub branching_factor: f64,
+    /// How many different diets the ants built
+    pub distinct_paths: usize,
+}
+
+/// Objectives of the diets built in one iteration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IterationStats {
+    pub best: f64,
+    pub mean: f64,
+    pub worst: f64,
+}
+
+/// Everything a single solver run produced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunResult {
+    pub solver: String,
+    pub best: Solution,
+    /// Iteration the best diet was found in, for the solvers that track it
+    #[serde(default)]
+    pub found_in: Option<usize>,
+    /// Best objective found so far, one entry per iteration (or generation)
+    pub history: Vec<f64>,
+    /// Spread of the objectives within each iteration, for the solvers that report it
+    #[serde(default)]
+    pub iterations: Vec<IterationStats>,
+    /// One entry per iteration for the solvers that report them
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostics>,
+    /// Objective of the greedy diet the solver started from, if it did
+    #[serde(default)]
+    pub baseline: Option<f64>,
+    /// Running the solver again with this seed reproduces the result
+    pub seed: u64,
+    /// How many diets were evaluated during the run
+    pub evaluations: u64,
+    pub elapsed: Duration,
+}
+
+/// Why a solver could not produce a result
+#[derive(Debug)]
+pub enum SolverError {
+    /// The underlying algorithm gave up, e.g. genevo rejecting the population
+    Algorithm(String),
+    /// Asked for zero iterations before any diet was built
+    NoIterations,
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            // genevo prefixes its messages with "algorithm error" already
+            SolverError::Algorithm(message) => write!(f, "{}", message),
+            SolverError::NoIterations => write!(f, "at least one iteration is needed to build a diet"),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+/// An optimisation algorithm searching for the best diet of a `DietProblem`
+pub trait Solver {
+    fn name(&self) -> String;
+
+    fn solve(&mut self, iterations: usize) -> Result<RunResult, SolverError>;
+}